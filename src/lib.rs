@@ -1,155 +1,157 @@
 pub mod structures;
 
+#[cfg(feature = "sim")]
+pub mod sim;
+
 #[cfg(test)]
 mod tests {
-    use std::{
-        thread,
-        time::{Duration, Instant},
-    };
+    use std::time::{Duration, SystemTime};
 
-    use super::structures::{block::Block, node::Node};
+    use super::structures::{
+        block::Block,
+        node::{Node, DEFAULT_DELTA},
+        time::{SystemClockSource, TestClock},
+    };
 
     #[test]
     fn protocol_execution() {
         // Genesis block is generated.
-        let mut genesis_block = Block::new(String::from("⊥"), 0, vec![]);
-        genesis_block.metadata.notarized = true;
-        genesis_block.metadata.finalized = true;
+        let genesis_block = Block::genesis();
 
-        let genesis_time = Instant::now();
+        let genesis_time = SystemTime::now();
+        // Nodes share a test clock, so epochs pass without waiting for them.
+        let clock = TestClock::new(genesis_time, DEFAULT_DELTA);
 
         // We create some nodes to participate in the Protocol.
-        let mut node0 = Node::new(0, genesis_time, genesis_block.clone());
-        let mut node1 = Node::new(1, genesis_time, genesis_block.clone());
-        let mut node2 = Node::new(2, genesis_time, genesis_block.clone());
-
-        // We store nodes public keys for voting.
-        let node0_keypair = node0.keypair.clone();
-        let node1_keypair = node1.keypair.clone();
-        let node2_keypair = node2.keypair.clone();
-
-        // We use thread sleep to simulate sinchronization period.
-        thread::sleep(Duration::new(10, 0));
+        let mut node0 =
+            Node::with_clock_source(0, genesis_time, genesis_block.clone(), &SystemClockSource)
+                .unwrap();
+        let mut node1 =
+            Node::with_clock_source(1, genesis_time, genesis_block.clone(), &SystemClockSource)
+                .unwrap();
+        let mut node2 =
+            Node::with_clock_source(2, genesis_time, genesis_block.clone(), &SystemClockSource)
+                .unwrap();
+
+        // We store nodes public keys for registration.
+        let node0_public_key = node0.public_key().unwrap();
+        let node1_public_key = node1.public_key().unwrap();
+        let node2_public_key = node2.public_key().unwrap();
+        // Each node registers all participants public keys and uses the test clock.
+        for node in [&mut node0, &mut node1, &mut node2] {
+            node.clock = Box::new(clock.clone());
+            node.register_peer(0, &node0_public_key).unwrap();
+            node.register_peer(1, &node1_public_key).unwrap();
+            node.register_peer(2, &node2_public_key).unwrap();
+        }
+
+        // We advance the clock to simulate sinchronization period.
+        clock.advance(Duration::new(10, 0));
 
         // We simulate some epochs to test consistency.
         let tx = String::from("tx0");
-        node0.receive_transaction(tx.clone());
-        node0.broadcast_transaction(vec![&mut node1, &mut node2], tx);
+        node0.receive_transaction(tx.clone()).unwrap();
+        node0.broadcast_transaction(vec![&mut node1, &mut node2], tx).unwrap();
         let tx = String::from("tx1");
-        node1.receive_transaction(tx.clone());
-        node1.broadcast_transaction(vec![&mut node0, &mut node2], tx);
+        node1.receive_transaction(tx.clone()).unwrap();
+        node1.broadcast_transaction(vec![&mut node0, &mut node2], tx).unwrap();
         let tx = String::from("tx2");
-        node2.receive_transaction(tx.clone());
-        node2.broadcast_transaction(vec![&mut node0, &mut node1], tx);
+        node2.receive_transaction(tx.clone()).unwrap();
+        node2.broadcast_transaction(vec![&mut node0, &mut node1], tx).unwrap();
 
         // Each node checks if they are the epoch leader. Leader will propose the block.
-        let (leader_keypair, block_proposal) = if node0.check_if_epoch_leader(3) {
-            node0.propose_block()
-        } else if node1.check_if_epoch_leader(3) {
-            node1.propose_block()
+        let (_, block_proposal) = if node0.check_if_epoch_leader() {
+            node0.propose_block().unwrap().unwrap()
+        } else if node1.check_if_epoch_leader() {
+            node1.propose_block().unwrap().unwrap()
         } else {
-            node2.propose_block()
+            node2.propose_block().unwrap().unwrap()
         };
 
         // Leader broadcasts the proposed_block to rest nodes and they vote on it.
-        let node0_vote = node0.receive_proposed_block(&leader_keypair, &block_proposal, 3).unwrap();
-        let node1_vote = node1.receive_proposed_block(&leader_keypair, &block_proposal, 3).unwrap();
-        let node2_vote = node2.receive_proposed_block(&leader_keypair, &block_proposal, 3).unwrap();
+        let node0_vote = node0.receive_proposed_block(&block_proposal).unwrap().unwrap();
+        let node1_vote = node1.receive_proposed_block(&block_proposal).unwrap().unwrap();
+        let node2_vote = node2.receive_proposed_block(&block_proposal).unwrap().unwrap();
 
         // Each node broadcasts its vote to rest nodes.
-        node0.receive_vote(&node0_keypair, &node0_vote, 3);
-        node0.receive_vote(&node1_keypair, &node1_vote, 3);
-        node0.receive_vote(&node2_keypair, &node2_vote, 3);
-        node1.receive_vote(&node0_keypair, &node0_vote, 3);
-        node1.receive_vote(&node1_keypair, &node1_vote, 3);
-        node1.receive_vote(&node2_keypair, &node2_vote, 3);
-        node2.receive_vote(&node0_keypair, &node0_vote, 3);
-        node2.receive_vote(&node1_keypair, &node1_vote, 3);
-        node2.receive_vote(&node2_keypair, &node2_vote, 3);
+        let votes = [node0_vote, node1_vote, node2_vote];
+        node0.receive_votes(&votes).unwrap();
+        node1.receive_votes(&votes).unwrap();
+        node2.receive_votes(&votes).unwrap();
 
         // We verify that all nodes have the same blockchain on round end.
         verify_outputs(&node0, &node1, &node2);
 
-        // We use thread sleep to simulate sinchronization period.
-        thread::sleep(Duration::new(10, 0));
+        // We advance the clock to simulate sinchronization period.
+        clock.advance(Duration::new(10, 0));
 
         // Next round.
         let tx = String::from("tx4");
-        node0.receive_transaction(tx.clone());
-        node0.broadcast_transaction(vec![&mut node1, &mut node2], tx);
+        node0.receive_transaction(tx.clone()).unwrap();
+        node0.broadcast_transaction(vec![&mut node1, &mut node2], tx).unwrap();
         let tx = String::from("tx5");
-        node1.receive_transaction(tx.clone());
-        node1.broadcast_transaction(vec![&mut node0, &mut node2], tx);
+        node1.receive_transaction(tx.clone()).unwrap();
+        node1.broadcast_transaction(vec![&mut node0, &mut node2], tx).unwrap();
         let tx = String::from("tx6");
-        node2.receive_transaction(tx.clone());
-        node2.broadcast_transaction(vec![&mut node0, &mut node1], tx);
+        node2.receive_transaction(tx.clone()).unwrap();
+        node2.broadcast_transaction(vec![&mut node0, &mut node1], tx).unwrap();
 
         // Each node checks if they are the epoch leader. Leader will propose the block.
-        let (leader_keypair, block_proposal) = if node0.check_if_epoch_leader(3) {
-            node0.propose_block()
-        } else if node1.check_if_epoch_leader(3) {
-            node1.propose_block()
+        let (_, block_proposal) = if node0.check_if_epoch_leader() {
+            node0.propose_block().unwrap().unwrap()
+        } else if node1.check_if_epoch_leader() {
+            node1.propose_block().unwrap().unwrap()
         } else {
-            node2.propose_block()
+            node2.propose_block().unwrap().unwrap()
         };
 
         // Leader broadcasts the proposed_block to rest nodes and they vote on it.
-        let node0_vote = node0.receive_proposed_block(&leader_keypair, &block_proposal, 3).unwrap();
-        let node1_vote = node1.receive_proposed_block(&leader_keypair, &block_proposal, 3).unwrap();
-        let node2_vote = node2.receive_proposed_block(&leader_keypair, &block_proposal, 3).unwrap();
+        let node0_vote = node0.receive_proposed_block(&block_proposal).unwrap().unwrap();
+        let node1_vote = node1.receive_proposed_block(&block_proposal).unwrap().unwrap();
+        let node2_vote = node2.receive_proposed_block(&block_proposal).unwrap().unwrap();
 
         // Each node broadcasts its vote to rest nodes.
-        node0.receive_vote(&node0_keypair, &node0_vote, 3);
-        node0.receive_vote(&node1_keypair, &node1_vote, 3);
-        node0.receive_vote(&node2_keypair, &node2_vote, 3);
-        node1.receive_vote(&node0_keypair, &node0_vote, 3);
-        node1.receive_vote(&node1_keypair, &node1_vote, 3);
-        node1.receive_vote(&node2_keypair, &node2_vote, 3);
-        node2.receive_vote(&node0_keypair, &node0_vote, 3);
-        node2.receive_vote(&node1_keypair, &node1_vote, 3);
-        node2.receive_vote(&node2_keypair, &node2_vote, 3);
+        let votes = [node0_vote, node1_vote, node2_vote];
+        node0.receive_votes(&votes).unwrap();
+        node1.receive_votes(&votes).unwrap();
+        node2.receive_votes(&votes).unwrap();
 
         // We verify that all nodes have the same blockchain on round end.
         verify_outputs(&node0, &node1, &node2);
 
-        // We use thread sleep to simulate sinchronization period.
-        thread::sleep(Duration::new(10, 0));
+        // We advance the clock to simulate sinchronization period.
+        clock.advance(Duration::new(10, 0));
 
         // Next round.
         let tx = String::from("tx7");
-        node0.receive_transaction(tx.clone());
-        node0.broadcast_transaction(vec![&mut node1, &mut node2], tx);
+        node0.receive_transaction(tx.clone()).unwrap();
+        node0.broadcast_transaction(vec![&mut node1, &mut node2], tx).unwrap();
         let tx = String::from("tx8");
-        node1.receive_transaction(tx.clone());
-        node1.broadcast_transaction(vec![&mut node0, &mut node2], tx);
+        node1.receive_transaction(tx.clone()).unwrap();
+        node1.broadcast_transaction(vec![&mut node0, &mut node2], tx).unwrap();
         let tx = String::from("tx9");
-        node2.receive_transaction(tx.clone());
-        node2.broadcast_transaction(vec![&mut node0, &mut node1], tx);
+        node2.receive_transaction(tx.clone()).unwrap();
+        node2.broadcast_transaction(vec![&mut node0, &mut node1], tx).unwrap();
 
         // Each node checks if they are the epoch leader. Leader will propose the block.
-        let (leader_keypair, block_proposal) = if node0.check_if_epoch_leader(3) {
-            node0.propose_block()
-        } else if node1.check_if_epoch_leader(3) {
-            node1.propose_block()
+        let (_, block_proposal) = if node0.check_if_epoch_leader() {
+            node0.propose_block().unwrap().unwrap()
+        } else if node1.check_if_epoch_leader() {
+            node1.propose_block().unwrap().unwrap()
         } else {
-            node2.propose_block()
+            node2.propose_block().unwrap().unwrap()
         };
 
         // Leader broadcasts the proposed_block to rest nodes and they vote on it.
-        let node0_vote = node0.receive_proposed_block(&leader_keypair, &block_proposal, 3).unwrap();
-        let node1_vote = node1.receive_proposed_block(&leader_keypair, &block_proposal, 3).unwrap();
-        let node2_vote = node2.receive_proposed_block(&leader_keypair, &block_proposal, 3).unwrap();
+        let node0_vote = node0.receive_proposed_block(&block_proposal).unwrap().unwrap();
+        let node1_vote = node1.receive_proposed_block(&block_proposal).unwrap().unwrap();
+        let node2_vote = node2.receive_proposed_block(&block_proposal).unwrap().unwrap();
 
         // Each node broadcasts its vote to rest nodes.
-        node0.receive_vote(&node0_keypair, &node0_vote, 3);
-        node0.receive_vote(&node1_keypair, &node1_vote, 3);
-        node0.receive_vote(&node2_keypair, &node2_vote, 3);
-        node1.receive_vote(&node0_keypair, &node0_vote, 3);
-        node1.receive_vote(&node1_keypair, &node1_vote, 3);
-        node1.receive_vote(&node2_keypair, &node2_vote, 3);
-        node2.receive_vote(&node0_keypair, &node0_vote, 3);
-        node2.receive_vote(&node1_keypair, &node1_vote, 3);
-        node2.receive_vote(&node2_keypair, &node2_vote, 3);
+        let votes = [node0_vote, node1_vote, node2_vote];
+        node0.receive_votes(&votes).unwrap();
+        node1.receive_votes(&votes).unwrap();
+        node2.receive_votes(&votes).unwrap();
 
         // We verify that all nodes have the same blockchain on round end.
         verify_outputs(&node0, &node1, &node2);
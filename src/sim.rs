@@ -0,0 +1,168 @@
+//! # Simulation
+//!
+//! A harness running the protocol among in-process nodes, without any networking.
+//! Instead of waiting for real epochs to pass, each simulated epoch moves the nodes genesis
+//! time back, so epochs are driven instantly.
+
+use std::time::{Duration, SystemTime};
+
+use openssl::pkey::PKey;
+
+use crate::structures::{
+    block::Block, blockchain::Blockchain, error::NodeError, node::Node, time::SystemClockSource,
+    vote::Vote,
+};
+
+/// Simulation of a network of validator nodes, running the protocol for a number of epochs.
+#[derive(Debug)]
+pub struct Simulation {
+    pub nodes: Vec<Node>,
+    /// Epoch duration parameter of all nodes
+    pub delta: u64,
+    /// Number of epochs to run, starting from epoch 1
+    pub epochs: u64,
+}
+
+impl Simulation {
+    /// Creates nodes_count validator nodes, sharing the genesis block and knowing each
+    /// other's public keys. Nodes sign using Ed25519, to keep key generation fast.
+    /// Delta must not be zero.
+    pub fn new(nodes_count: u64, delta: u64, epochs: u64) -> Result<Simulation, NodeError> {
+        let genesis_block = Block::genesis();
+        let mut nodes = Vec::new();
+        for id in 0..nodes_count {
+            let keypair = PKey::generate_ed25519()?;
+            let mut node = Node::with_keypair_and_clock_source(
+                id,
+                SystemTime::now(),
+                genesis_block.clone(),
+                keypair,
+                &SystemClockSource,
+            )?;
+            node.set_delta(delta)?;
+            nodes.push(node);
+        }
+
+        let mut public_keys = Vec::new();
+        for node in &nodes {
+            public_keys.push((node.id, node.public_key()?));
+        }
+        for node in &mut nodes {
+            for (id, public_key) in &public_keys {
+                node.register_peer(*id, public_key)?;
+            }
+        }
+
+        Ok(Simulation { nodes, delta, epochs })
+    }
+
+    /// Runs all epochs, returning each node finalized blockchain.
+    pub fn run(&mut self) -> Result<Vec<Blockchain>, NodeError> {
+        for epoch in 1..=self.epochs {
+            self.run_epoch(epoch)?;
+        }
+        Ok(self.nodes.iter().map(|node| node.output().clone()).collect())
+    }
+
+    /// Runs a single epoch: all nodes receive a new transaction, the epoch leader proposes
+    /// a block, nodes vote on it and all votes are delivered to every node.
+    pub fn run_epoch(&mut self, epoch: u64) -> Result<(), NodeError> {
+        // Nodes are placed in the middle of the epoch, away from its boundaries
+        let elapsed = Duration::from_secs(epoch * 2 * self.delta + self.delta);
+        let genesis_time = SystemTime::now() - elapsed;
+        for node in &mut self.nodes {
+            node.genesis_time = genesis_time;
+        }
+
+        let transaction = format!("tx{}", epoch);
+        for node in &mut self.nodes {
+            node.receive_transaction(transaction.clone())?;
+        }
+
+        let Some(leader) = self.nodes.iter().position(|node| node.check_if_epoch_leader()) else {
+            return Ok(())
+        };
+        let Some((_, proposal)) = self.nodes[leader].propose_block()? else { return Ok(()) };
+
+        let mut votes: Vec<Vote> = Vec::new();
+        for node in &mut self.nodes {
+            if let Some(vote) = node.receive_proposed_block(&proposal)? {
+                votes.push(vote);
+            }
+        }
+        for node in &mut self.nodes {
+            node.receive_votes(&votes)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Simulation;
+    use crate::structures::{
+        audit::verify_finalized_chain,
+        error::{NodeError, ValidationError},
+        metadata::Metadata,
+        vote::Vote,
+    };
+
+    /// Notarized block metadata holding provided votes.
+    fn notarized_metadata(votes: Vec<Vote>) -> Metadata {
+        let mut metadata = Metadata::new();
+        for vote in votes {
+            metadata.add_vote(vote);
+        }
+        metadata.notarize();
+        metadata
+    }
+
+    #[test]
+    fn five_nodes_agree() {
+        let mut simulation = Simulation::new(5, 5, 6).unwrap();
+        let chains = simulation.run().unwrap();
+        assert_eq!(chains.len(), 5);
+        for chain in &chains {
+            assert_eq!(chain, &chains[0]);
+        }
+        // Last two epochs blocks remain notarized, but not finalized.
+        assert_eq!(chains[0].height(), 5);
+        assert!(chains[0].validate().is_ok());
+        let transactions: Vec<&String> = chains[0].blocks.iter().flat_map(|b| b.txs()).collect();
+        assert_eq!(transactions, ["tx1", "tx2", "tx3", "tx4"]);
+    }
+
+    #[test]
+    fn zero_delta_is_rejected() {
+        assert!(matches!(Simulation::new(3, 0, 1), Err(NodeError::InvalidDelta)));
+    }
+
+    #[test]
+    fn simulated_chain_passes_audit() {
+        let mut simulation = Simulation::new(4, 5, 6).unwrap();
+        let chains = simulation.run().unwrap();
+        let keys = &simulation.nodes[0].registry.nodes;
+        verify_finalized_chain(&chains[0], keys, 4).unwrap();
+
+        // Threshold is 3 votes, so dropping two of them invalidates the block.
+        let mut chain = chains[0].clone();
+        let votes = chain.blocks[2].metadata.votes()[..2].to_vec();
+        chain.blocks[2].metadata = notarized_metadata(votes);
+        assert_eq!(
+            verify_finalized_chain(&chain, keys, 4),
+            Err(ValidationError::InsufficientVotes { index: 2 })
+        );
+
+        // Forged votes don't count.
+        let mut chain = chains[0].clone();
+        let mut votes = chain.blocks[1].metadata.votes().to_vec();
+        for vote in &mut votes[..2] {
+            vote.vote = vec![0; 64];
+        }
+        chain.blocks[1].metadata = notarized_metadata(votes);
+        assert_eq!(
+            verify_finalized_chain(&chain, keys, 4),
+            Err(ValidationError::InsufficientVotes { index: 1 })
+        );
+    }
+}
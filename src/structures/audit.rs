@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use openssl::pkey::{PKey, Public};
+
+use super::{blockchain::Blockchain, error::ValidationError, node::Node};
+
+/// Verifies a finalized blockchain independently of any node, given the participants public
+/// keys. Blocks must link to each other, and every block apart from genesis must carry valid
+/// votes from more than 2n/3 distinct participants. Votes from unknown ids, for a different
+/// block or with an invalid signature are ignored. The signature scheme of each vote is
+/// derived from its sender key type.
+/// nodes_count is a u64, like the node counts across the node and certificate APIs it is
+/// checked against, rather than a usize.
+pub fn verify_finalized_chain(
+    chain: &Blockchain,
+    keys: &HashMap<u64, PKey<Public>>,
+    nodes_count: u64,
+) -> Result<(), ValidationError> {
+    chain.validate()?;
+    let threshold = Node::notarization_threshold(nodes_count);
+    for (index, block) in chain.iter().enumerate() {
+        if block.is_genesis() {
+            continue
+        }
+        let mut voters = Vec::new();
+        for vote in block.metadata.votes() {
+            let Some(key) = keys.get(&vote.id) else { continue };
+            if vote.block != *block || voters.contains(&vote.id) {
+                continue
+            }
+            if vote.verify(key) {
+                voters.push(vote.id);
+            }
+        }
+        if (voters.len() as u64) < threshold {
+            return Err(ValidationError::InsufficientVotes { index })
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::verify_finalized_chain;
+    use crate::structures::{
+        block::Block,
+        blockchain::Blockchain,
+        error::ValidationError,
+        metadata::Metadata,
+        test_utils::{keypairs, public_keys, sign_vote},
+    };
+
+    #[test]
+    fn finalized_chain_audit() {
+        let keypairs = keypairs(4);
+        let keys = public_keys(&keypairs);
+        // Chain of two blocks, each voted by 3 out of 4 nodes.
+        let mut chain = Blockchain::new(Block::genesis());
+        for epoch in 1..3 {
+            let parent_hash = chain.tip().unwrap().hash_digest();
+            let mut block = Block::new(parent_hash, epoch, vec![format!("tx{}", epoch)]);
+            for (id, keypair) in keypairs.iter().enumerate().skip(1) {
+                block.metadata.add_vote(sign_vote(keypair, &block, id as u64));
+            }
+            block.metadata.notarize();
+            chain.add_block(&block).unwrap();
+        }
+        assert_eq!(verify_finalized_chain(&chain, &keys, 4), Ok(()));
+
+        // Threshold is 3 votes, and votes from unknown nodes don't count.
+        let mut unknown_keys = keys.clone();
+        unknown_keys.remove(&3);
+        assert_eq!(
+            verify_finalized_chain(&chain, &unknown_keys, 4),
+            Err(ValidationError::InsufficientVotes { index: 1 })
+        );
+
+        // Votes for a different block don't count either.
+        let mut tampered = chain.clone();
+        let mut metadata = Metadata::new();
+        metadata.add_vote(sign_vote(&keypairs[1], &chain.blocks[1], 1));
+        for vote in &chain.blocks[2].metadata.votes()[1..] {
+            metadata.add_vote(vote.clone());
+        }
+        tampered.blocks[2].metadata = metadata;
+        assert_eq!(
+            verify_finalized_chain(&tampered, &keys, 4),
+            Err(ValidationError::InsufficientVotes { index: 2 })
+        );
+
+        // Broken linkage is reported before any votes are checked.
+        let mut broken = chain;
+        broken.blocks[1].txs_mut().push(String::from("tx0"));
+        assert_eq!(
+            verify_finalized_chain(&broken, &keys, 4),
+            Err(ValidationError::BrokenLink { index: 2 })
+        );
+    }
+}
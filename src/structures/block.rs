@@ -1,40 +1,311 @@
-use std::hash::{Hash, Hasher};
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+    sync::OnceLock,
+    time::Duration,
+};
 
-use super::metadata::Metadata;
+use openssl::hash::{hash, MessageDigest};
+
+use super::{error::BlockHashError, metadata::Metadata};
+
+/// Parent hash placeholder of the genesis block.
+pub const GENESIS_PARENT_HASH: &str = "⊥";
+
+/// Length of block hashes, in bytes.
+pub const BLOCK_HASH_LEN: usize = 32;
+
+/// This struct represents a SHA-256 block hash.
+/// It is displayed and parsed as lowercase hex, the encoding parent hashes are stored with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockHash(pub [u8; BLOCK_HASH_LEN]);
+
+impl fmt::Display for BlockHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for BlockHash {
+    type Err = BlockHashError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 2 * BLOCK_HASH_LEN {
+            return Err(BlockHashError::InvalidLength(s.len()))
+        }
+        if !s.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(BlockHashError::InvalidHex)
+        }
+        let mut bytes = [0; BLOCK_HASH_LEN];
+        for (byte, pair) in bytes.iter_mut().zip(s.as_bytes().chunks(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|_| BlockHashError::InvalidHex)?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| BlockHashError::InvalidHex)?;
+        }
+        Ok(BlockHash(bytes))
+    }
+}
 
 /// This struct represents a tuple of the form (h, e, txs, metadata).
 /// Each blocks parent hash h may be computed simply as a hash of the parent block.
 #[derive(Debug, Clone)]
 pub struct Block {
     /// Parent hash
-    pub h: String,
+    h: String,
     /// Epoch number
-    pub e: u64,
+    e: u64,
     /// Transactions payload
-    pub txs: Vec<String>,
+    txs: Vec<String>,
     /// Additional block information
     pub metadata: Metadata,
+    /// Hash digest, computed on first use and cleared when the block content changes
+    hash_cache: OnceLock<BlockHash>,
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Number of hash digests actually computed by the current thread.
+    static DIGEST_COMPUTATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
 }
 
 impl Block {
     pub fn new(h: String, e: u64, txs: Vec<String>) -> Block {
-        Block { h, e, txs, metadata: Metadata::new() }
+        Block { h, e, txs, metadata: Metadata::new(), hash_cache: OnceLock::new() }
+    }
+
+    /// Parent hash.
+    pub fn h(&self) -> &str {
+        &self.h
+    }
+
+    /// Epoch number.
+    pub fn e(&self) -> u64 {
+        self.e
+    }
+
+    /// Transactions payload.
+    pub fn txs(&self) -> &[String] {
+        &self.txs
+    }
+
+    /// Mutable transactions payload. Block content changes, so its cached hash is cleared.
+    pub fn txs_mut(&mut self) -> &mut Vec<String> {
+        self.hash_cache = OnceLock::new();
+        &mut self.txs
+    }
+
+    /// Block construction with a metadata timestamp, relative to genesis.
+    /// Proposers use it to tie the block timestamp to its epoch start.
+    pub fn new_at(h: String, e: u64, txs: Vec<String>, timestamp: Duration) -> Block {
+        let mut block = Block::new(h, e, txs);
+        block.metadata.timestamp = Some(timestamp);
+        block
+    }
+
+    /// Canonical genesis block, with no parent, epoch 0 and no transactions.
+    /// Genesis is notarized and finalized by definition.
+    pub fn genesis() -> Block {
+        let mut genesis_block = Block::new(String::from(GENESIS_PARENT_HASH), 0, vec![]);
+        genesis_block.metadata.notarize();
+        genesis_block.metadata.finalize();
+        genesis_block
+    }
+
+    /// Checks that the block is a genesis block, having no parent and epoch 0.
+    /// Any payload is allowed, so networks can start from a custom genesis.
+    pub fn is_genesis(&self) -> bool {
+        self.h == GENESIS_PARENT_HASH && self.e == 0
     }
 
     pub fn signature_encode(&self) -> Vec<u8> {
         let signature = format!("{:?}{:?}{:?}", self.h, self.e, self.txs);
         signature.as_bytes().to_vec()
     }
+
+    /// Number of votes the block has received.
+    pub fn vote_count(&self) -> usize {
+        self.metadata.vote_count()
+    }
+
+    /// Block hash used for parent linkage, hex encoded. See block_hash.
+    pub fn hash_digest(&self) -> String {
+        self.block_hash().to_string()
+    }
+
+    /// Typed block hash, computed as the SHA-256 digest of the block signature encoding.
+    /// Hash is cached, and only recomputed after the block content changes.
+    pub fn block_hash(&self) -> BlockHash {
+        *self.hash_cache.get_or_init(|| self.compute_block_hash())
+    }
+
+    /// Typed parent hash. Genesis placeholder, or any other non hash value, has none.
+    pub fn parent_hash(&self) -> Option<BlockHash> {
+        self.h.parse().ok()
+    }
+
+    fn compute_block_hash(&self) -> BlockHash {
+        #[cfg(test)]
+        DIGEST_COMPUTATIONS.with(|computations| computations.set(computations.get() + 1));
+        let digest = hash(MessageDigest::sha256(), &self.signature_encode())
+            .expect("SHA-256 digest computation failed");
+        let mut bytes = [0; BLOCK_HASH_LEN];
+        bytes.copy_from_slice(&digest);
+        BlockHash(bytes)
+    }
 }
 
+/// Blocks are compared by content, ignoring per-node metadata like votes and timestamps.
 impl PartialEq for Block {
     fn eq(&self, other: &Self) -> bool {
         self.h == other.h && self.e == other.e && self.txs == other.txs
     }
 }
 
+impl Eq for Block {}
+
+impl_serde_struct!(Block { h: String, e: u64, txs: Vec<String>, metadata: Metadata } skip {
+    hash_cache
+});
+
+/// Compact block representation, showing the epoch, parent hash prefix, transactions
+/// and notarization/finalization flags.
+impl fmt::Display for Block {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parent_hash: String = self.h.chars().take(8).collect();
+        write!(f, "epoch:{} hash:{} txs:[{}]", self.e, parent_hash, self.txs.join(", "))?;
+        if self.metadata.is_notarized() {
+            write!(f, " notarized")?;
+        }
+        if self.metadata.is_finalized() {
+            write!(f, " finalized")?;
+        }
+        Ok(())
+    }
+}
+
+/// Hash implementation for map usage. Chain linkage relies on hash_digest instead.
 impl Hash for Block {
     fn hash<H: Hasher>(&self, hasher: &mut H) {
         (&self.h, &self.e, &self.txs).hash(hasher);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::{Block, BlockHash, DIGEST_COMPUTATIONS, GENESIS_PARENT_HASH};
+    use crate::structures::{blockchain::Blockchain, error::BlockHashError, vote::Vote};
+
+    #[test]
+    fn hash_digest_is_stable_sha256() {
+        let block = Block::genesis();
+        let digest = block.hash_digest();
+        assert_eq!(digest.len(), 64);
+        assert_eq!(digest, Block::genesis().hash_digest());
+        assert_ne!(digest, Block::new(String::from(GENESIS_PARENT_HASH), 1, vec![]).hash_digest());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let genesis_block = Block::genesis();
+        let mut block = Block::new(genesis_block.hash_digest(), 1, vec![String::from("tx0")]);
+        block.metadata.add_vote(Vote::new(vec![1, 2, 3], block.clone(), 0));
+        block.metadata.add_vote(Vote::new(vec![4, 5, 6], block.clone(), 1));
+        block.metadata.notarize();
+
+        let json = serde_json::to_string(&block).unwrap();
+        let decoded: Block = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, block);
+        assert_eq!(decoded.metadata, block.metadata);
+
+        let mut blockchain = Blockchain::new(genesis_block);
+        blockchain.add_block(&block).unwrap();
+        let json = serde_json::to_string(&blockchain).unwrap();
+        let decoded: Blockchain = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, blockchain);
+        assert_eq!(decoded.blocks[1].metadata, block.metadata);
+    }
+
+    #[test]
+    fn genesis_validation() {
+        let genesis_block = Block::genesis();
+        assert!(genesis_block.is_genesis());
+        assert!(genesis_block.metadata.votes().is_empty());
+
+        // Genesis is structural: custom payloads are allowed, other parents or epochs aren't.
+        let custom = Block::new(String::from(GENESIS_PARENT_HASH), 0, vec![String::from("tx0")]);
+        assert!(custom.is_genesis());
+        assert!(!Block::new(String::from(GENESIS_PARENT_HASH), 1, vec![]).is_genesis());
+        assert!(!Block::new(genesis_block.hash_digest(), 0, vec![]).is_genesis());
+        assert!(!Block::new(genesis_block.hash_digest(), 1, vec![]).is_genesis());
+    }
+
+    #[test]
+    fn explicit_timestamp() {
+        let timestamp = Duration::from_secs(2);
+        let block = Block::new_at(Block::genesis().hash_digest(), 1, vec![], timestamp);
+        assert_eq!(block.metadata.timestamp, Some(timestamp));
+        // Timestamp is metadata, so it doesn't affect the block identity.
+        assert_eq!(block, Block::new(Block::genesis().hash_digest(), 1, vec![]));
+    }
+
+    #[test]
+    fn identical_blocks_are_equal_regardless_of_creation_time() {
+        let parent_hash = Block::genesis().hash_digest();
+        let block0 = Block::new(parent_hash.clone(), 1, vec![String::from("tx0")]);
+        thread::sleep(Duration::from_millis(10));
+        let block1 = Block::new(parent_hash, 1, vec![String::from("tx0")]);
+        assert_eq!(block0.metadata.timestamp, None);
+        assert_eq!(block0.metadata, block1.metadata);
+        assert_eq!(block0, block1);
+        assert_eq!(
+            serde_json::to_string(&block0).unwrap(),
+            serde_json::to_string(&block1).unwrap()
+        );
+    }
+
+    #[test]
+    fn hash_digest_is_cached() {
+        let computations = || DIGEST_COMPUTATIONS.with(|computations| computations.get());
+        let mut block = Block::new(Block::genesis().hash_digest(), 1, vec![]);
+        let start = computations();
+        let digest = block.hash_digest();
+        for _ in 0..10 {
+            assert_eq!(block.hash_digest(), digest);
+        }
+        // Clones share the cached digest.
+        assert_eq!(block.clone().hash_digest(), digest);
+        assert_eq!(computations() - start, 1);
+
+        // Changing the block content invalidates the cache.
+        block.txs_mut().push(String::from("tx0"));
+        let new_digest = block.hash_digest();
+        assert_ne!(new_digest, digest);
+        assert_eq!(block.hash_digest(), new_digest);
+        assert_eq!(computations() - start, 2);
+        let rebuilt = Block::new(block.h().to_string(), 1, block.txs().to_vec());
+        assert_eq!(new_digest, rebuilt.hash_digest());
+    }
+
+    #[test]
+    fn block_hash_hex_round_trip() {
+        let genesis_block = Block::genesis();
+        let block_hash = genesis_block.block_hash();
+        assert_eq!(block_hash.to_string(), genesis_block.hash_digest());
+        assert_eq!(genesis_block.hash_digest().parse::<BlockHash>(), Ok(block_hash));
+        assert_eq!(block_hash.to_string().to_uppercase().parse::<BlockHash>(), Ok(block_hash));
+
+        let block = Block::new(genesis_block.hash_digest(), 1, vec![]);
+        assert_eq!(block.parent_hash(), Some(block_hash));
+        assert_ne!(block.block_hash(), block_hash);
+        assert_eq!(genesis_block.parent_hash(), None);
+
+        assert_eq!("00".parse::<BlockHash>(), Err(BlockHashError::InvalidLength(2)));
+        let non_hex = format!("+{}", &block_hash.to_string()[1..]);
+        assert_eq!(non_hex.parse::<BlockHash>(), Err(BlockHashError::InvalidHex));
+    }
+}
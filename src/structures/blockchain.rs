@@ -1,12 +1,15 @@
 use std::{
-    collections::hash_map::DefaultHasher,
-    hash::{Hash, Hasher},
+    fmt,
+    io::{self, Write},
+    slice,
 };
 
-use super::block::Block;
+use super::{block::Block, error::ValidationError};
 
 /// This struct represents a sequence of blocks starting with the genesis block.
-#[derive(Debug, Clone, PartialEq)]
+/// Blockchains are equal when their blocks content match, so outputs of different nodes
+/// can be compared directly.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Blockchain {
     pub blocks: Vec<Block>,
 }
@@ -16,39 +19,372 @@ impl Blockchain {
         Blockchain { blocks: vec![intial_block] }
     }
 
-    /// A block is considered valid when its parent hash is equal to the hash of the
-    /// previous block and their epochs are incremental, exluding genesis.
-    /// Additional validity rules can be applied.
-    pub fn check_block_validity(&self, block: &Block, previous_block: &Block) {
-        assert!(block.h != "⊥", "Genesis block provided.");
-        let mut hasher = DefaultHasher::new();
-        previous_block.hash(&mut hasher);
-        assert!(
-            block.h == hasher.finish().to_string() && block.e > previous_block.e,
-            "Provided block is invalid."
-        );
+    /// Branches off provided chain, copying its first up_to blocks as the shared prefix and
+    /// appending the new block, which must extend the last copied block.
+    /// up_to can't exceed the parent chain height.
+    pub fn fork_from(
+        parent_chain: &Blockchain,
+        up_to: usize,
+        new_block: Block,
+    ) -> Result<Blockchain, ValidationError> {
+        let prefix = parent_chain
+            .blocks
+            .get(..up_to)
+            .ok_or(ValidationError::ForkPointOutOfRange { up_to })?;
+        let mut blockchain = Blockchain { blocks: prefix.to_vec() };
+        blockchain.add_block(&new_block)?;
+        Ok(blockchain)
+    }
+
+    /// Last block of the blockchain, if any.
+    pub fn tip(&self) -> Option<&Block> {
+        self.blocks.last()
     }
 
-    /// A blockchain is considered valid, when every block is valid, based on check_block_validity method.
-    pub fn check_chain_validity(&self) {
-        for (index, block) in self.blocks[1..].iter().enumerate() {
-            self.check_block_validity(&block, &self.blocks[index])
+    /// Iterates over the blockchain blocks, in order.
+    pub fn iter(&self) -> slice::Iter<'_, Block> {
+        self.blocks.iter()
+    }
+
+    /// Blocks of provided epoch. A valid blockchain contains at most one.
+    pub fn blocks_by_epoch(&self, e: u64) -> impl Iterator<Item = &Block> {
+        self.iter().filter(move |block| block.e() == e)
+    }
+
+    /// Number of blocks in the blockchain.
+    pub fn height(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Blockchain integrity check, for chains received from other nodes.
+    /// Each block must link to the hash of its predecessor and advance its epoch.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        for (index, pair) in self.blocks.windows(2).enumerate() {
+            let (previous_block, block) = (&pair[0], &pair[1]);
+            if block.h() != previous_block.hash_digest() {
+                return Err(ValidationError::BrokenLink { index: index + 1 })
+            }
+            if block.e() <= previous_block.e() {
+                return Err(ValidationError::NonIncreasingEpoch { index: index + 1 })
+            }
         }
+        Ok(())
     }
 
-    /// Insertion of a valid block.
-    pub fn add_block(&mut self, block: &Block) {
-        self.check_block_validity(&block, &self.blocks.last().unwrap());
+    /// Insertion of a valid block. Block must extend the current tip, linking to its hash
+    /// and advancing its epoch, otherwise it is rejected.
+    pub fn add_block(&mut self, block: &Block) -> Result<(), ValidationError> {
+        if let Some(tip) = self.blocks.last() {
+            let index = self.blocks.len();
+            if block.h() != tip.hash_digest() {
+                return Err(ValidationError::BrokenLink { index })
+            }
+            if block.e() <= tip.e() {
+                return Err(ValidationError::NonIncreasingEpoch { index })
+            }
+        }
         self.blocks.push(block.clone());
+        Ok(())
     }
 
     /// Blockchain notarization check.
     pub fn is_notarized(&self) -> bool {
         for block in &self.blocks {
-            if !block.metadata.notarized {
+            if !block.metadata.is_notarized() {
                 return false
             }
         }
         true
     }
+
+    /// Number of leading blocks that are notarized.
+    /// Equals the blockchain height when the whole chain is notarized.
+    pub fn notarized_prefix_len(&self) -> usize {
+        self.blocks.iter().take_while(|block| block.metadata.is_notarized()).count()
+    }
+
+    /// Number of transactions across all blockchain blocks.
+    pub fn total_transactions(&self) -> usize {
+        self.iter().map(|block| block.txs().len()).sum()
+    }
+
+    /// Position of the first occurrence of provided transaction, as its block index and
+    /// its index inside that block.
+    pub fn find_transaction(&self, tx: &str) -> Option<(usize, usize)> {
+        self.iter().enumerate().find_map(|(block_index, block)| {
+            block
+                .txs()
+                .iter()
+                .position(|block_tx| block_tx == tx)
+                .map(|tx_index| (block_index, tx_index))
+        })
+    }
+
+    /// Number of leading blocks both blockchains share, compared by content.
+    /// Chains diverge right after their common prefix.
+    pub fn common_prefix_len(&self, other: &Blockchain) -> usize {
+        self.iter().zip(other).take_while(|(block, other_block)| block == other_block).count()
+    }
+
+    /// Writes the blockchain as JSON Lines: each block serialized as a JSON object on its
+    /// own line, so the output can be streamed into log-processing tools.
+    pub fn write_jsonl<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        for block in self {
+            serde_json::to_writer(&mut *w, block)?;
+            w.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a Blockchain {
+    type Item = &'a Block;
+    type IntoIter = slice::Iter<'a, Block>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Blockchain representation, one block per line.
+impl fmt::Display for Blockchain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for block in self {
+            writeln!(f, "{}", block)?;
+        }
+        Ok(())
+    }
+}
+
+impl_serde_struct!(Blockchain { blocks: Vec<Block> });
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Blockchain;
+    use crate::structures::{block::Block, error::ValidationError, vote::Vote};
+
+    #[test]
+    fn tip_and_height() {
+        let genesis_block = Block::genesis();
+        let mut blockchain = Blockchain::new(genesis_block.clone());
+        assert_eq!(blockchain.height(), 1);
+        assert_eq!(blockchain.tip(), Some(&genesis_block));
+
+        let block = Block::new(genesis_block.hash_digest(), 1, vec![]);
+        blockchain.add_block(&block).unwrap();
+        assert_eq!(blockchain.height(), 2);
+        assert_eq!(blockchain.tip(), Some(&block));
+
+        blockchain.blocks.clear();
+        assert_eq!(blockchain.height(), 0);
+        assert_eq!(blockchain.tip(), None);
+    }
+
+    #[test]
+    fn validate_linkage_and_epochs() {
+        let genesis_block = Block::genesis();
+        let mut blockchain = Blockchain::new(genesis_block.clone());
+        let block1 = Block::new(genesis_block.hash_digest(), 1, vec![]);
+        blockchain.add_block(&block1).unwrap();
+        blockchain.add_block(&Block::new(block1.hash_digest(), 2, vec![])).unwrap();
+        assert_eq!(blockchain.validate(), Ok(()));
+        assert_eq!(Blockchain { blocks: vec![] }.validate(), Ok(()));
+
+        let mut broken = blockchain.clone();
+        broken.blocks[1].txs_mut().push(String::from("tx0"));
+        assert_eq!(broken.validate(), Err(ValidationError::BrokenLink { index: 2 }));
+
+        let mut stalled = blockchain;
+        stalled.blocks[2] = Block::new(block1.hash_digest(), 1, vec![]);
+        assert_eq!(stalled.validate(), Err(ValidationError::NonIncreasingEpoch { index: 2 }));
+    }
+
+    #[test]
+    fn equality_ignores_node_metadata() {
+        let genesis_block = Block::genesis();
+        let block = Block::new(genesis_block.hash_digest(), 1, vec![String::from("tx0")]);
+        let mut blockchain0 = Blockchain::new(genesis_block.clone());
+        blockchain0.add_block(&block).unwrap();
+        let mut blockchain1 = blockchain0.clone();
+
+        blockchain1.blocks[1].metadata.timestamp = Some(Duration::from_secs(5));
+        blockchain1.blocks[1].metadata.add_vote(Vote::new(vec![1], block.clone(), 0));
+        assert_ne!(blockchain0.blocks[1].metadata, blockchain1.blocks[1].metadata);
+        assert_eq!(blockchain0, blockchain1);
+
+        blockchain1.blocks[1].txs_mut().push(String::from("tx1"));
+        assert_ne!(blockchain0, blockchain1);
+    }
+
+    #[test]
+    fn add_block_rejects_non_extending_blocks() {
+        let genesis_block = Block::genesis();
+        let mut blockchain = Blockchain::new(genesis_block.clone());
+        let block1 = Block::new(genesis_block.hash_digest(), 1, vec![]);
+        blockchain.add_block(&block1).unwrap();
+        assert_eq!(blockchain.tip(), Some(&block1));
+
+        let unrelated = Block::new(genesis_block.hash_digest(), 2, vec![]);
+        assert_eq!(blockchain.add_block(&unrelated), Err(ValidationError::BrokenLink { index: 2 }));
+        let stale = Block::new(block1.hash_digest(), 1, vec![]);
+        assert_eq!(
+            blockchain.add_block(&stale),
+            Err(ValidationError::NonIncreasingEpoch { index: 2 })
+        );
+        assert_eq!(blockchain.height(), 2);
+    }
+
+    #[test]
+    fn display_lists_blocks_and_flags() {
+        let genesis_block = Block::genesis();
+        let mut blockchain = Blockchain::new(genesis_block.clone());
+        let block = Block::new(genesis_block.hash_digest(), 1, vec![String::from("tx0")]);
+        blockchain.add_block(&block).unwrap();
+        blockchain.blocks[1].metadata.notarize();
+
+        let output = blockchain.to_string();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "epoch:0 hash:⊥ txs:[] notarized finalized");
+        assert_eq!(
+            lines[1],
+            format!("epoch:1 hash:{} txs:[tx0] notarized", &genesis_block.hash_digest()[..8])
+        );
+    }
+
+    #[test]
+    fn fork_from_keeps_shared_ancestors() {
+        let genesis_block = Block::genesis();
+        let block1 = Block::new(genesis_block.hash_digest(), 1, vec![]);
+        let block2 = Block::new(block1.hash_digest(), 2, vec![]);
+        let mut blockchain = Blockchain::new(genesis_block.clone());
+        blockchain.add_block(&block1).unwrap();
+        blockchain.add_block(&block2).unwrap();
+
+        // Branching off block1 keeps genesis and block1, dropping block2.
+        let block3 = Block::new(block1.hash_digest(), 3, vec![String::from("tx0")]);
+        let fork = Blockchain::fork_from(&blockchain, 2, block3.clone()).unwrap();
+        assert_eq!(fork.blocks, vec![genesis_block, block1, block3]);
+        assert!(fork.validate().is_ok());
+        assert_eq!(blockchain.height(), 3);
+
+        // New block must extend the last shared block.
+        let block4 = Block::new(block2.hash_digest(), 4, vec![]);
+        assert_eq!(
+            Blockchain::fork_from(&blockchain, 2, block4.clone()),
+            Err(ValidationError::BrokenLink { index: 2 })
+        );
+
+        // Fork point can't lie past the parent chain end.
+        assert!(Blockchain::fork_from(&blockchain, 3, block4.clone()).is_ok());
+        assert_eq!(
+            Blockchain::fork_from(&blockchain, 4, block4),
+            Err(ValidationError::ForkPointOutOfRange { up_to: 4 })
+        );
+    }
+
+    #[test]
+    fn notarized_prefix_length() {
+        let genesis_block = Block::genesis();
+        let block1 = Block::new(genesis_block.hash_digest(), 1, vec![]);
+        let block2 = Block::new(block1.hash_digest(), 2, vec![]);
+        let mut blockchain = Blockchain { blocks: vec![] };
+        assert_eq!(blockchain.notarized_prefix_len(), 0);
+
+        blockchain.add_block(&block1).unwrap();
+        blockchain.add_block(&block2).unwrap();
+        assert_eq!(blockchain.notarized_prefix_len(), 0);
+
+        // Partially notarized chain.
+        blockchain.blocks[0].metadata.notarize();
+        assert_eq!(blockchain.notarized_prefix_len(), 1);
+        assert!(!blockchain.is_notarized());
+
+        // Fully notarized chain.
+        blockchain.blocks[1].metadata.notarize();
+        assert_eq!(blockchain.notarized_prefix_len(), 2);
+        assert!(blockchain.is_notarized());
+    }
+
+    #[test]
+    fn iterate_blocks() {
+        let genesis_block = Block::genesis();
+        let block1 = Block::new(genesis_block.hash_digest(), 1, vec![]);
+        let block3 = Block::new(block1.hash_digest(), 3, vec![]);
+        let mut blockchain = Blockchain::new(genesis_block);
+        blockchain.add_block(&block1).unwrap();
+        blockchain.add_block(&block3).unwrap();
+
+        let epochs: Vec<u64> = blockchain.iter().map(|block| block.e()).collect();
+        assert_eq!(epochs, vec![0, 1, 3]);
+        let mut epochs = Vec::new();
+        for block in &blockchain {
+            epochs.push(block.e());
+        }
+        assert_eq!(epochs, vec![0, 1, 3]);
+
+        assert_eq!(blockchain.blocks_by_epoch(3).collect::<Vec<_>>(), vec![&block3]);
+        assert_eq!(blockchain.blocks_by_epoch(2).count(), 0);
+    }
+
+    #[test]
+    fn common_prefix_of_two_chains() {
+        let genesis_block = Block::genesis();
+        let block1 = Block::new(genesis_block.hash_digest(), 1, vec![]);
+        let block2 = Block::new(block1.hash_digest(), 2, vec![]);
+        let mut blockchain = Blockchain::new(genesis_block.clone());
+        blockchain.add_block(&block1).unwrap();
+        blockchain.add_block(&block2).unwrap();
+
+        // Identical chains share every block, regardless of metadata.
+        let mut identical = blockchain.clone();
+        identical.blocks[2].metadata.notarize();
+        assert_eq!(blockchain.common_prefix_len(&identical), 3);
+
+        // Diverging chains share blocks up to the branch point, in both directions.
+        let block3 = Block::new(block1.hash_digest(), 3, vec![String::from("tx0")]);
+        let fork = Blockchain::fork_from(&blockchain, 2, block3).unwrap();
+        assert_eq!(blockchain.common_prefix_len(&fork), 2);
+        assert_eq!(fork.common_prefix_len(&blockchain), 2);
+        let shorter = Blockchain::new(genesis_block.clone());
+        assert_eq!(blockchain.common_prefix_len(&shorter), 1);
+
+        // Chains with different genesis blocks share nothing.
+        let other_genesis = Block::new(String::from("other"), 0, vec![]);
+        assert_eq!(blockchain.common_prefix_len(&Blockchain::new(other_genesis)), 0);
+    }
+
+    #[test]
+    fn transaction_lookup() {
+        let genesis_block = Block::genesis();
+        let block1 = Block::new(genesis_block.hash_digest(), 1, vec![String::from("tx0")]);
+        let txs = vec![String::from("tx1"), String::from("tx2")];
+        let block2 = Block::new(block1.hash_digest(), 2, txs);
+        let mut blockchain = Blockchain::new(genesis_block);
+        assert_eq!(blockchain.total_transactions(), 0);
+        blockchain.add_block(&block1).unwrap();
+        blockchain.add_block(&block2).unwrap();
+
+        assert_eq!(blockchain.total_transactions(), 3);
+        assert_eq!(blockchain.find_transaction("tx0"), Some((1, 0)));
+        assert_eq!(blockchain.find_transaction("tx2"), Some((2, 1)));
+        assert_eq!(blockchain.find_transaction("tx3"), None);
+    }
+
+    #[test]
+    fn jsonl_export() {
+        let mut blockchain = Blockchain::new(Block::genesis());
+        let block = Block::new(Block::genesis().hash_digest(), 1, vec![String::from("tx0")]);
+        blockchain.add_block(&block).unwrap();
+
+        let mut output = Vec::new();
+        blockchain.write_jsonl(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), blockchain.height());
+        for (line, block) in lines.iter().zip(&blockchain) {
+            assert_eq!(serde_json::from_str::<Block>(line).unwrap(), *block);
+        }
+    }
 }
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use openssl::pkey::{PKey, Public};
+
+use super::{block::Block, node::Node, vote::Vote};
+
+/// This struct represents a compact proof of a block notarization: the block along with the
+/// (id, signature) pairs of its votes. Nodes can distribute a single certificate, instead of
+/// every individual vote.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotarizationCertificate {
+    /// Notarized block
+    pub block: Block,
+    /// Node id and vote signature pairs
+    pub signatures: Vec<(u64, Vec<u8>)>,
+}
+
+impl NotarizationCertificate {
+    /// Builds a certificate out of the votes held in the block metadata.
+    /// Votes for a different block are left out.
+    pub fn from_block(block: &Block) -> NotarizationCertificate {
+        let mut unsigned_block = block.clone();
+        unsigned_block.metadata.clear_votes();
+        let signatures = block
+            .metadata
+            .votes()
+            .iter()
+            .filter(|vote| vote.block == *block)
+            .map(|vote| (vote.id, vote.vote.clone()))
+            .collect();
+        NotarizationCertificate { block: unsigned_block, signatures }
+    }
+
+    /// Expands the certificate back into individual votes, so they can be received by a node.
+    pub fn votes(&self) -> Vec<Vote> {
+        let mut block = self.block.clone();
+        block.metadata.clear_votes();
+        self.signatures
+            .iter()
+            .map(|(id, signature)| Vote::new(signature.clone(), block.clone(), *id))
+            .collect()
+    }
+
+    /// Checks that the certificate carries valid vote signatures from more than 2n/3
+    /// distinct participants, given their public keys. Signatures from unknown ids or
+    /// not verifying are ignored.
+    pub fn verify(&self, keys: &HashMap<u64, PKey<Public>>, nodes_count: u64) -> bool {
+        let mut signers = Vec::new();
+        for vote in self.votes() {
+            let Some(key) = keys.get(&vote.id) else { continue };
+            if !signers.contains(&vote.id) && vote.verify(key) {
+                signers.push(vote.id);
+            }
+        }
+        signers.len() as u64 >= Node::notarization_threshold(nodes_count)
+    }
+}
+
+impl_serde_struct!(NotarizationCertificate { block: Block, signatures: Vec<(u64, Vec<u8>)> });
+
+#[cfg(test)]
+mod tests {
+    use super::NotarizationCertificate;
+    use crate::structures::{
+        block::Block,
+        test_utils::{keypairs, public_keys, sign_vote},
+    };
+
+    #[test]
+    fn certificate_from_notarized_block() {
+        let keypairs = keypairs(4);
+        let keys = public_keys(&keypairs);
+        let mut block = Block::new(Block::genesis().hash_digest(), 1, vec![String::from("tx0")]);
+        for (id, keypair) in keypairs.iter().enumerate().take(3) {
+            let vote = sign_vote(keypair, &block, id as u64);
+            block.metadata.add_vote(vote);
+        }
+        block.metadata.notarize();
+
+        let certificate = NotarizationCertificate::from_block(&block);
+        assert_eq!(certificate.signatures.len(), 3);
+        assert!(certificate.verify(&keys, 4));
+        assert_eq!(certificate.votes(), block.metadata.votes());
+        let json = serde_json::to_string(&certificate).unwrap();
+        assert_eq!(serde_json::from_str::<NotarizationCertificate>(&json).unwrap(), certificate);
+
+        // Threshold is 3 votes out of 4 nodes, duplicated signers count once.
+        let mut duplicated = certificate.clone();
+        duplicated.signatures[2] = duplicated.signatures[0].clone();
+        assert!(!duplicated.verify(&keys, 4));
+
+        // Invalid signatures and unknown signers don't count.
+        let mut forged = certificate.clone();
+        forged.signatures[1].1 = vec![0; 64];
+        assert!(!forged.verify(&keys, 4));
+        let mut unknown_keys = keys.clone();
+        unknown_keys.remove(&2);
+        assert!(!certificate.verify(&unknown_keys, 4));
+
+        // Signatures don't carry over to a different block.
+        let mut tampered = certificate;
+        tampered.block.txs_mut().push(String::from("tx1"));
+        assert!(!tampered.verify(&keys, 4));
+    }
+}
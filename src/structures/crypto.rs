@@ -0,0 +1,177 @@
+use std::fmt::Debug;
+
+use openssl::{
+    hash::MessageDigest,
+    pkey::{HasPublic, Id, PKey, Private, Public},
+    sign::{Signer, Verifier},
+};
+
+#[cfg(feature = "pure-ed25519")]
+use super::ed25519;
+use super::error::NodeError;
+
+/// This trait represents a signature scheme over OpenSSL keys. Nodes hold a scheme,
+/// matching the type of their keypair, to sign their messages and verify their peers ones.
+pub trait SignatureScheme: Debug + Send + Sync {
+    /// Signs provided message.
+    fn sign(&self, secret_key: &PKey<Private>, message: &[u8]) -> Result<Vec<u8>, NodeError>;
+
+    /// Verifies provided signature over message.
+    fn verify(
+        &self,
+        public_key: &PKey<Public>,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, NodeError>;
+}
+
+/// RSA signatures over SHA-256 digests.
+#[derive(Debug, Clone, Copy)]
+pub struct RsaSha256Scheme;
+
+impl SignatureScheme for RsaSha256Scheme {
+    fn sign(&self, secret_key: &PKey<Private>, message: &[u8]) -> Result<Vec<u8>, NodeError> {
+        let mut signer = Signer::new(MessageDigest::sha256(), secret_key)?;
+        signer.update(message)?;
+        Ok(signer.sign_to_vec()?)
+    }
+
+    fn verify(
+        &self,
+        public_key: &PKey<Public>,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, NodeError> {
+        let mut verifier = Verifier::new(MessageDigest::sha256(), public_key)?;
+        verifier.update(message)?;
+        Ok(verifier.verify(signature)?)
+    }
+}
+
+/// Ed25519 signatures. Keys and signatures are much smaller than RSA ones,
+/// and faster to produce.
+#[derive(Debug, Clone, Copy)]
+pub struct Ed25519Scheme;
+
+impl SignatureScheme for Ed25519Scheme {
+    fn sign(&self, secret_key: &PKey<Private>, message: &[u8]) -> Result<Vec<u8>, NodeError> {
+        let mut signer = Signer::new_without_digest(secret_key)?;
+        Ok(signer.sign_oneshot_to_vec(message)?)
+    }
+
+    fn verify(
+        &self,
+        public_key: &PKey<Public>,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, NodeError> {
+        let mut verifier = Verifier::new_without_digest(public_key)?;
+        Ok(verifier.verify_oneshot(signature, message)?)
+    }
+}
+
+/// Ed25519 signatures computed in pure Rust, interoperable with Ed25519Scheme ones.
+/// OpenSSL only holds the keys, their raw bytes being used for signing and verification.
+#[cfg(feature = "pure-ed25519")]
+#[derive(Debug, Clone, Copy)]
+pub struct PureEd25519Scheme;
+
+#[cfg(feature = "pure-ed25519")]
+impl SignatureScheme for PureEd25519Scheme {
+    fn sign(&self, secret_key: &PKey<Private>, message: &[u8]) -> Result<Vec<u8>, NodeError> {
+        let secret_key = raw_key_bytes(secret_key.raw_private_key()?)?;
+        Ok(ed25519::sign(&secret_key, message).to_vec())
+    }
+
+    fn verify(
+        &self,
+        public_key: &PKey<Public>,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, NodeError> {
+        let public_key = raw_key_bytes(public_key.raw_public_key()?)?;
+        Ok(ed25519::verify(&public_key, message, signature))
+    }
+}
+
+/// Checks raw key bytes have the Ed25519 key length.
+#[cfg(feature = "pure-ed25519")]
+fn raw_key_bytes(bytes: Vec<u8>) -> Result<[u8; 32], NodeError> {
+    bytes.try_into().map_err(|_| NodeError::InvalidKey)
+}
+
+/// Extracts the public half of provided key.
+pub fn public_key<T: HasPublic>(key: &PKey<T>) -> Result<PKey<Public>, NodeError> {
+    Ok(PKey::public_key_from_der(&key.public_key_to_der()?)?)
+}
+
+/// Signature scheme matching provided key type: Ed25519 keys use Ed25519Scheme,
+/// rest use RsaSha256Scheme.
+/// With the pure-ed25519 feature, Ed25519 keys use PureEd25519Scheme instead.
+pub fn scheme_for_key<T>(key: &PKey<T>) -> &'static dyn SignatureScheme {
+    match key.id() {
+        #[cfg(feature = "pure-ed25519")]
+        Id::ED25519 => &PureEd25519Scheme,
+        #[cfg(not(feature = "pure-ed25519"))]
+        Id::ED25519 => &Ed25519Scheme,
+        _ => &RsaSha256Scheme,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{pkey::PKey, rsa::Rsa};
+
+    use super::{public_key, scheme_for_key, Ed25519Scheme, RsaSha256Scheme, SignatureScheme};
+
+    #[test]
+    fn schemes_sign_and_verify() {
+        let rsa_keypair = PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap();
+        let ed25519_keypair = PKey::generate_ed25519().unwrap();
+        let schemes: [(&dyn SignatureScheme, _); 2] =
+            [(&RsaSha256Scheme, rsa_keypair), (&Ed25519Scheme, ed25519_keypair)];
+        for (scheme, keypair) in schemes {
+            let public_key = public_key(&keypair).unwrap();
+            let signature = scheme.sign(&keypair, b"block").unwrap();
+            assert!(scheme.verify(&public_key, b"block", &signature).unwrap());
+            assert!(!scheme.verify(&public_key, b"other block", &signature).unwrap());
+        }
+
+        let ed25519_keypair = PKey::generate_ed25519().unwrap();
+        assert_eq!(Ed25519Scheme.sign(&ed25519_keypair, b"block").unwrap().len(), 64);
+    }
+
+    #[test]
+    fn scheme_matches_key_type() {
+        for keypair in [
+            PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap(),
+            PKey::generate_ed25519().unwrap(),
+        ] {
+            let scheme = scheme_for_key(&keypair);
+            let signature = scheme.sign(&keypair, b"block").unwrap();
+            let public_key = public_key(&keypair).unwrap();
+            assert!(scheme_for_key(&public_key).verify(&public_key, b"block", &signature).unwrap());
+        }
+    }
+
+    #[cfg(feature = "pure-ed25519")]
+    #[test]
+    fn pure_ed25519_scheme_interoperates_with_openssl() {
+        use super::PureEd25519Scheme;
+
+        let keypair = PKey::generate_ed25519().unwrap();
+        let public_key = public_key(&keypair).unwrap();
+        let signature = PureEd25519Scheme.sign(&keypair, b"block").unwrap();
+        assert!(PureEd25519Scheme.verify(&public_key, b"block", &signature).unwrap());
+        assert!(!PureEd25519Scheme.verify(&public_key, b"other block", &signature).unwrap());
+
+        // Ed25519 signatures are deterministic, so both schemes produce the same ones.
+        assert_eq!(signature, Ed25519Scheme.sign(&keypair, b"block").unwrap());
+        assert!(Ed25519Scheme.verify(&public_key, b"block", &signature).unwrap());
+        let signature = Ed25519Scheme.sign(&keypair, b"other block").unwrap();
+        assert!(PureEd25519Scheme.verify(&public_key, b"other block", &signature).unwrap());
+
+        let rsa_keypair = PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap();
+        assert!(PureEd25519Scheme.sign(&rsa_keypair, b"block").is_err());
+    }
+}
@@ -0,0 +1,614 @@
+//! Pure-Rust Ed25519 signatures, as specified in RFC 8032, along with the SHA-512 digest
+//! they are built on.
+//! Field elements are kept in five 51-bit limbs and curve points in extended coordinates.
+//! Secret scalars are multiplied through a fixed sequence of operations, regardless of
+//! their bits.
+
+use std::sync::OnceLock;
+
+/// SHA-512 round constants.
+const K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+/// SHA-512 initial hash values.
+const H: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// Incremental SHA-512 digest.
+struct Sha512 {
+    state: [u64; 8],
+    buffer: Vec<u8>,
+    len: u128,
+}
+
+impl Sha512 {
+    fn new() -> Sha512 {
+        Sha512 { state: H, buffer: Vec::with_capacity(128), len: 0 }
+    }
+
+    fn update(&mut self, data: &[u8]) -> &mut Sha512 {
+        self.len += data.len() as u128;
+        self.buffer.extend_from_slice(data);
+        let full = self.buffer.len() - self.buffer.len() % 128;
+        for block in self.buffer[..full].chunks_exact(128) {
+            Sha512::compress(&mut self.state, block);
+        }
+        self.buffer.drain(..full);
+        self
+    }
+
+    fn finalize(&mut self) -> [u8; 64] {
+        let bit_len = self.len * 8;
+        let mut padding = vec![0x80];
+        padding.resize((239 - self.buffer.len()) % 128 + 1, 0);
+        padding.extend_from_slice(&bit_len.to_be_bytes());
+        self.update(&padding);
+        let mut digest = [0; 64];
+        for (chunk, word) in digest.chunks_exact_mut(8).zip(self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    fn compress(state: &mut [u64; 8], block: &[u8]) {
+        let mut w = [0u64; 80];
+        for (i, word) in block.chunks_exact(8).enumerate() {
+            w[i] = u64::from_be_bytes(word.try_into().unwrap());
+        }
+        for i in 16..80 {
+            let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+            let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for i in 0..80 {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            (h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+}
+
+/// SHA-512 digest of provided data.
+pub(crate) fn sha512(data: &[u8]) -> [u8; 64] {
+    Sha512::new().update(data).finalize()
+}
+
+const LIMB_MASK: u64 = (1 << 51) - 1;
+
+/// Exponent p - 2, used to invert field elements, little-endian.
+const P_MINUS_2: [u8; 32] = exponent(0xeb, 0x7f);
+/// Exponent (p - 5) / 8, used to compute square roots, little-endian.
+const P_MINUS_5_DIV_8: [u8; 32] = exponent(0xfd, 0x0f);
+/// Exponent (p - 1) / 4, raising 2 to which gives a square root of -1, little-endian.
+const P_MINUS_1_DIV_4: [u8; 32] = exponent(0xfb, 0x1f);
+
+/// Little-endian exponent with provided lowest and highest bytes, all others set.
+const fn exponent(low: u8, high: u8) -> [u8; 32] {
+    let mut bytes = [0xff; 32];
+    bytes[0] = low;
+    bytes[31] = high;
+    bytes
+}
+
+/// Element of the field of integers modulo p = 2^255 - 19.
+#[derive(Debug, Clone, Copy)]
+struct FieldElement([u64; 5]);
+
+impl FieldElement {
+    const ZERO: FieldElement = FieldElement([0; 5]);
+    const ONE: FieldElement = FieldElement([1, 0, 0, 0, 0]);
+
+    fn from_u64(value: u64) -> FieldElement {
+        FieldElement([value, 0, 0, 0, 0])
+    }
+
+    /// Decodes a little-endian element, ignoring the highest bit.
+    fn from_bytes(bytes: &[u8; 32]) -> FieldElement {
+        let load = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+        FieldElement([
+            load(0) & LIMB_MASK,
+            (load(6) >> 3) & LIMB_MASK,
+            (load(12) >> 6) & LIMB_MASK,
+            (load(19) >> 1) & LIMB_MASK,
+            (load(24) >> 12) & LIMB_MASK,
+        ])
+    }
+
+    /// Encodes the element fully reduced modulo p, little-endian.
+    fn to_bytes(self) -> [u8; 32] {
+        let mut limbs = FieldElement::carry(FieldElement::carry(self.0).0).0;
+        // Limbs are below 2^51 here, so the value is at least p only if adding 19 carries
+        // past the highest bit.
+        let mut q = (limbs[0] + 19) >> 51;
+        for limb in &limbs[1..] {
+            q = (limb + q) >> 51;
+        }
+        limbs[0] += 19 * q;
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= LIMB_MASK;
+        }
+        limbs[4] &= LIMB_MASK;
+
+        let mut bytes = [0; 32];
+        let (mut acc, mut bits, mut index) = (0u128, 0, 0);
+        for limb in limbs {
+            acc |= (limb as u128) << bits;
+            bits += 51;
+            while bits >= 8 {
+                bytes[index] = acc as u8;
+                acc >>= 8;
+                bits -= 8;
+                index += 1;
+            }
+        }
+        bytes[index] = acc as u8;
+        bytes
+    }
+
+    /// Propagates limbs carries, folding the top one back as multiples of 19.
+    fn carry(mut limbs: [u64; 5]) -> FieldElement {
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= LIMB_MASK;
+        }
+        limbs[0] += 19 * (limbs[4] >> 51);
+        limbs[4] &= LIMB_MASK;
+        limbs[1] += limbs[0] >> 51;
+        limbs[0] &= LIMB_MASK;
+        FieldElement(limbs)
+    }
+
+    fn add(&self, other: &FieldElement) -> FieldElement {
+        let mut limbs = self.0;
+        for (limb, other) in limbs.iter_mut().zip(other.0) {
+            *limb += other;
+        }
+        FieldElement::carry(limbs)
+    }
+
+    fn sub(&self, other: &FieldElement) -> FieldElement {
+        // Adding 2p first keeps limbs from underflowing.
+        const TWO_P: [u64; 5] =
+            [0xfffffffffffda, 0xffffffffffffe, 0xffffffffffffe, 0xffffffffffffe, 0xffffffffffffe];
+        let mut limbs = self.0;
+        for i in 0..5 {
+            limbs[i] = limbs[i] + TWO_P[i] - other.0[i];
+        }
+        FieldElement::carry(limbs)
+    }
+
+    fn neg(&self) -> FieldElement {
+        FieldElement::ZERO.sub(self)
+    }
+
+    fn mul(&self, other: &FieldElement) -> FieldElement {
+        let m = |x: u64, y: u64| x as u128 * y as u128;
+        let [a0, a1, a2, a3, a4] = self.0;
+        let [b0, b1, b2, b3, b4] = other.0;
+        let (b1_19, b2_19, b3_19, b4_19) = (b1 * 19, b2 * 19, b3 * 19, b4 * 19);
+        let mut c = [
+            m(a0, b0) + m(a1, b4_19) + m(a2, b3_19) + m(a3, b2_19) + m(a4, b1_19),
+            m(a0, b1) + m(a1, b0) + m(a2, b4_19) + m(a3, b3_19) + m(a4, b2_19),
+            m(a0, b2) + m(a1, b1) + m(a2, b0) + m(a3, b4_19) + m(a4, b3_19),
+            m(a0, b3) + m(a1, b2) + m(a2, b1) + m(a3, b0) + m(a4, b4_19),
+            m(a0, b4) + m(a1, b3) + m(a2, b2) + m(a3, b1) + m(a4, b0),
+        ];
+        for i in 0..4 {
+            c[i + 1] += c[i] >> 51;
+            c[i] &= LIMB_MASK as u128;
+        }
+        c[0] += 19 * (c[4] >> 51);
+        c[4] &= LIMB_MASK as u128;
+        c[1] += c[0] >> 51;
+        c[0] &= LIMB_MASK as u128;
+        FieldElement::carry(c.map(|limb| limb as u64))
+    }
+
+    fn square(&self) -> FieldElement {
+        self.mul(self)
+    }
+
+    /// Raises the element to provided public little-endian exponent.
+    fn pow(&self, exponent: &[u8; 32]) -> FieldElement {
+        let mut result = FieldElement::ONE;
+        for i in (0..256).rev() {
+            result = result.square();
+            if (exponent[i / 8] >> (i % 8)) & 1 == 1 {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+
+    fn invert(&self) -> FieldElement {
+        self.pow(&P_MINUS_2)
+    }
+
+    fn is_negative(&self) -> bool {
+        self.to_bytes()[0] & 1 == 1
+    }
+
+    fn equals(&self, other: &FieldElement) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+
+    /// Picks other if choice is 1, self if it is 0, without branching on it.
+    fn select(&self, other: &FieldElement, choice: u8) -> FieldElement {
+        let mask = 0u64.wrapping_sub(choice as u64);
+        let mut limbs = self.0;
+        for (limb, other) in limbs.iter_mut().zip(other.0) {
+            *limb ^= mask & (*limb ^ other);
+        }
+        FieldElement(limbs)
+    }
+}
+
+/// Curve constants which are derived at first use.
+struct Constants {
+    /// Twice the curve constant d = -121665 / 121666.
+    d2: FieldElement,
+    /// Curve constant d.
+    d: FieldElement,
+    /// A square root of -1.
+    sqrt_m1: FieldElement,
+    /// Curve base point.
+    base: Point,
+}
+
+fn constants() -> &'static Constants {
+    static CONSTANTS: OnceLock<Constants> = OnceLock::new();
+    CONSTANTS.get_or_init(|| {
+        let d = FieldElement::from_u64(121665).neg().mul(&FieldElement::from_u64(121666).invert());
+        let sqrt_m1 = FieldElement::from_u64(2).pow(&P_MINUS_1_DIV_4);
+        let mut base_encoding = [0x66; 32];
+        base_encoding[0] = 0x58;
+        let base = Point::decompress(&base_encoding, &d, &sqrt_m1).unwrap();
+        Constants { d2: d.add(&d), d, sqrt_m1, base }
+    })
+}
+
+/// Point of the twisted Edwards curve -x^2 + y^2 = 1 + d x^2 y^2, in extended
+/// coordinates (X : Y : Z : T), with x = X/Z, y = Y/Z and x y = T/Z.
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement,
+}
+
+impl Point {
+    const IDENTITY: Point = Point {
+        x: FieldElement::ZERO,
+        y: FieldElement::ONE,
+        z: FieldElement::ONE,
+        t: FieldElement::ZERO,
+    };
+
+    /// Adds provided point, with the complete formula, also valid for doubling.
+    fn add(&self, other: &Point) -> Point {
+        let a = self.y.sub(&self.x).mul(&other.y.sub(&other.x));
+        let b = self.y.add(&self.x).mul(&other.y.add(&other.x));
+        let c = self.t.mul(&constants().d2).mul(&other.t);
+        let zz = self.z.mul(&other.z);
+        let d = zz.add(&zz);
+        let (e, f, g, h) = (b.sub(&a), d.sub(&c), d.add(&c), b.add(&a));
+        Point { x: e.mul(&f), y: g.mul(&h), z: f.mul(&g), t: e.mul(&h) }
+    }
+
+    fn neg(&self) -> Point {
+        Point { x: self.x.neg(), y: self.y, z: self.z, t: self.t.neg() }
+    }
+
+    /// Multiplies the point by provided little-endian scalar. Every bit costs a doubling
+    /// and an addition, whatever its value.
+    fn mul(&self, scalar: &[u8; 32]) -> Point {
+        let mut result = Point::IDENTITY;
+        for i in (0..256).rev() {
+            result = result.add(&result);
+            let sum = result.add(self);
+            let bit = (scalar[i / 8] >> (i % 8)) & 1;
+            result = Point {
+                x: result.x.select(&sum.x, bit),
+                y: result.y.select(&sum.y, bit),
+                z: result.z.select(&sum.z, bit),
+                t: result.t.select(&sum.t, bit),
+            };
+        }
+        result
+    }
+
+    /// Encodes the point as its y coordinate, with the sign of x in the highest bit.
+    fn compress(&self) -> [u8; 32] {
+        let z_inverse = self.z.invert();
+        let mut bytes = self.y.mul(&z_inverse).to_bytes();
+        bytes[31] |= (self.x.mul(&z_inverse).is_negative() as u8) << 7;
+        bytes
+    }
+
+    /// Decodes a point encoding, if it is canonical and lies on the curve.
+    fn decompress(bytes: &[u8; 32], d: &FieldElement, sqrt_m1: &FieldElement) -> Option<Point> {
+        let x_negative = bytes[31] >> 7 == 1;
+        let y = FieldElement::from_bytes(bytes);
+        let mut y_bytes = *bytes;
+        y_bytes[31] &= 0x7f;
+        if y.to_bytes() != y_bytes {
+            return None
+        }
+        // x^2 = u / v, with u = y^2 - 1 and v = d y^2 + 1.
+        let y2 = y.square();
+        let u = y2.sub(&FieldElement::ONE);
+        let v = d.mul(&y2).add(&FieldElement::ONE);
+        let v3 = v.square().mul(&v);
+        let v7 = v3.square().mul(&v);
+        let mut x = u.mul(&v3).mul(&u.mul(&v7).pow(&P_MINUS_5_DIV_8));
+        let vx2 = v.mul(&x.square());
+        if vx2.equals(&u.neg()) {
+            x = x.mul(sqrt_m1);
+        } else if !vx2.equals(&u) {
+            return None
+        }
+        if x.equals(&FieldElement::ZERO) && x_negative {
+            return None
+        }
+        if x.is_negative() != x_negative {
+            x = x.neg();
+        }
+        Some(Point { x, y, z: FieldElement::ONE, t: x.mul(&y) })
+    }
+}
+
+/// Group order L = 2^252 + 27742317777372353535851937790883648493, little-endian.
+const L: [u8; 32] = [
+    0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10,
+];
+
+/// Reduces a 512-bit little-endian number, held in bytes sized signed limbs, modulo L.
+fn reduce_limbs(mut x: [i64; 64]) -> [u8; 32] {
+    for i in (32..64).rev() {
+        let mut carry = 0;
+        for j in (i - 32)..(i - 12) {
+            x[j] += carry - 16 * x[i] * L[j - (i - 32)] as i64;
+            carry = (x[j] + 128) >> 8;
+            x[j] -= carry << 8;
+        }
+        x[i - 12] += carry;
+        x[i] = 0;
+    }
+    let mut carry = 0;
+    for j in 0..32 {
+        x[j] += carry - (x[31] >> 4) * L[j] as i64;
+        carry = x[j] >> 8;
+        x[j] &= 255;
+    }
+    for j in 0..32 {
+        x[j] -= carry * L[j] as i64;
+    }
+    let mut reduced = [0; 32];
+    for i in 0..32 {
+        x[i + 1] += x[i] >> 8;
+        reduced[i] = (x[i] & 255) as u8;
+    }
+    reduced
+}
+
+/// Reduces a 64 bytes little-endian number, like a SHA-512 digest, modulo L.
+fn reduce_scalar(bytes: &[u8; 64]) -> [u8; 32] {
+    reduce_limbs(bytes.map(i64::from))
+}
+
+/// Computes (a b + c) mod L, over little-endian scalars.
+fn mul_add_scalars(a: &[u8; 32], b: &[u8; 32], c: &[u8; 32]) -> [u8; 32] {
+    let mut x = [0i64; 64];
+    for (i, byte) in c.iter().enumerate() {
+        x[i] = *byte as i64;
+    }
+    for i in 0..32 {
+        for j in 0..32 {
+            x[i + j] += a[i] as i64 * b[j] as i64;
+        }
+    }
+    reduce_limbs(x)
+}
+
+/// Checks if provided little-endian scalar is below L, i.e. canonical.
+fn is_canonical_scalar(scalar: &[u8; 32]) -> bool {
+    for i in (0..32).rev() {
+        if scalar[i] != L[i] {
+            return scalar[i] < L[i]
+        }
+    }
+    false
+}
+
+/// Expands a 32 bytes secret key into its clamped scalar and signing nonce prefix.
+fn expand_secret(secret_key: &[u8; 32]) -> ([u8; 32], [u8; 32]) {
+    let digest = sha512(secret_key);
+    let mut scalar: [u8; 32] = digest[..32].try_into().unwrap();
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+    (scalar, digest[32..].try_into().unwrap())
+}
+
+/// Public key matching provided 32 bytes secret key.
+pub(crate) fn public_key(secret_key: &[u8; 32]) -> [u8; 32] {
+    let (scalar, _) = expand_secret(secret_key);
+    constants().base.mul(&scalar).compress()
+}
+
+/// Signs provided message with a 32 bytes secret key, producing a 64 bytes signature.
+pub(crate) fn sign(secret_key: &[u8; 32], message: &[u8]) -> [u8; 64] {
+    let (scalar, prefix) = expand_secret(secret_key);
+    let public_key = public_key(secret_key);
+    let nonce = reduce_scalar(&Sha512::new().update(&prefix).update(message).finalize());
+    let r = constants().base.mul(&nonce).compress();
+    let challenge =
+        reduce_scalar(&Sha512::new().update(&r).update(&public_key).update(message).finalize());
+    let s = mul_add_scalars(&challenge, &scalar, &nonce);
+    let mut signature = [0; 64];
+    signature[..32].copy_from_slice(&r);
+    signature[32..].copy_from_slice(&s);
+    signature
+}
+
+/// Verifies a signature over provided message against a 32 bytes public key.
+/// Malformed keys and signatures don't verify.
+pub(crate) fn verify(public_key: &[u8; 32], message: &[u8], signature: &[u8]) -> bool {
+    let Ok(signature) = <&[u8; 64]>::try_from(signature) else { return false };
+    let (r, s): (&[u8; 32], &[u8; 32]) =
+        (signature[..32].try_into().unwrap(), signature[32..].try_into().unwrap());
+    if !is_canonical_scalar(s) {
+        return false
+    }
+    let Some(point) = Point::decompress(public_key, &constants().d, &constants().sqrt_m1) else {
+        return false
+    };
+    let challenge =
+        reduce_scalar(&Sha512::new().update(r).update(public_key).update(message).finalize());
+    // R = [S]B - [k]A
+    let expected = constants().base.mul(s).add(&point.neg().mul(&challenge));
+    expected.compress() == *r
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::sha::sha512 as openssl_sha512;
+
+    use super::{public_key, sha512, sign, verify};
+
+    fn hex(bytes: &str) -> Vec<u8> {
+        (0..bytes.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&bytes[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn sha512_matches_openssl() {
+        for len in [0, 3, 111, 112, 127, 128, 129, 300] {
+            let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            assert_eq!(sha512(&data), openssl_sha512(&data));
+        }
+    }
+
+    #[test]
+    fn rfc8032_test_vector() {
+        // RFC 8032, section 7.1, test 1.
+        let secret_key: [u8; 32] =
+            hex("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60")
+                .try_into()
+                .unwrap();
+        let expected_public_key =
+            hex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a");
+        let expected_signature = hex(concat!(
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bac",
+            "c61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+        ));
+        let public_key = public_key(&secret_key);
+        assert_eq!(public_key.to_vec(), expected_public_key);
+        let signature = sign(&secret_key, b"");
+        assert_eq!(signature.to_vec(), expected_signature);
+        assert!(verify(&public_key, b"", &signature));
+        assert!(!verify(&public_key, b"block", &signature));
+        assert!(!verify(&public_key, b"", &signature[..63]));
+    }
+}
@@ -0,0 +1,127 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt::Debug,
+    hash::{Hash, Hasher},
+};
+
+/// This trait represents the strategy nodes use to elect each epoch leader.
+/// All nodes must use the same strategy to agree on the leaders.
+pub trait LeaderElection: Debug + Send + Sync {
+    /// Elects the leader id of provided epoch, among nodes_count participants.
+    fn leader(&self, epoch: u64, nodes_count: u64) -> u64;
+}
+
+/// Leader is elected by hashing the epoch number.
+/// Default strategy, spreading leadership pseudo-randomly across epochs.
+#[derive(Debug, Clone, Copy)]
+pub struct HashLeaderElection;
+
+impl LeaderElection for HashLeaderElection {
+    fn leader(&self, epoch: u64, nodes_count: u64) -> u64 {
+        epoch_hash(epoch) % nodes_count
+    }
+}
+
+/// Leader is elected by hashing the epoch number into the total stake, so each node leads
+/// proportionally to its stake. Nodes without stake never lead, while if no participant has
+/// stake, election falls back to hashing over all of them.
+#[derive(Debug, Clone, Default)]
+pub struct StakeWeightedLeaderElection {
+    /// Stake of each node id
+    pub stakes: HashMap<u64, u64>,
+}
+
+impl StakeWeightedLeaderElection {
+    pub fn new(stakes: HashMap<u64, u64>) -> StakeWeightedLeaderElection {
+        StakeWeightedLeaderElection { stakes }
+    }
+}
+
+impl LeaderElection for StakeWeightedLeaderElection {
+    fn leader(&self, epoch: u64, nodes_count: u64) -> u64 {
+        // Only participants stakes count, walked in id order so all nodes agree
+        let mut stakes: Vec<(u64, u64)> = self
+            .stakes
+            .iter()
+            .filter(|(id, stake)| **id < nodes_count && **stake > 0)
+            .map(|(id, stake)| (*id, *stake))
+            .collect();
+        stakes.sort_unstable();
+        let total_stake: u64 = stakes.iter().map(|(_, stake)| stake).sum();
+        if total_stake == 0 {
+            return epoch_hash(epoch) % nodes_count
+        }
+
+        let mut target = epoch_hash(epoch) % total_stake;
+        for (id, stake) in stakes {
+            if target < stake {
+                return id
+            }
+            target -= stake;
+        }
+        unreachable!("target is lower than the total stake")
+    }
+}
+
+/// Nodes lead in turns, by id. Useful for deterministic tests.
+#[derive(Debug, Clone, Copy)]
+pub struct RoundRobinLeaderElection;
+
+impl LeaderElection for RoundRobinLeaderElection {
+    fn leader(&self, epoch: u64, nodes_count: u64) -> u64 {
+        epoch % nodes_count
+    }
+}
+
+// Hashes the epoch number, seeding pseudo-random leader selection.
+fn epoch_hash(epoch: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    epoch.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{
+        HashLeaderElection, LeaderElection, RoundRobinLeaderElection, StakeWeightedLeaderElection,
+    };
+
+    #[test]
+    fn round_robin_cycles_leaders() {
+        let leaders: Vec<u64> =
+            (0..7).map(|epoch| RoundRobinLeaderElection.leader(epoch, 3)).collect();
+        assert_eq!(leaders, vec![0, 1, 2, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn hash_election_is_stable_and_bounded() {
+        for epoch in 0..20 {
+            let leader = HashLeaderElection.leader(epoch, 4);
+            assert!(leader < 4);
+            assert_eq!(leader, HashLeaderElection.leader(epoch, 4));
+        }
+    }
+
+    #[test]
+    fn stake_weighted_election_follows_stakes() {
+        let stakes = HashMap::from([(0, 10), (1, 20), (2, 70), (3, 0), (7, 50)]);
+        let election = StakeWeightedLeaderElection::new(stakes);
+        let epochs = 10_000;
+        let mut counts = [0_u64; 4];
+        for epoch in 0..epochs {
+            counts[election.leader(epoch, 4) as usize] += 1;
+        }
+        // Node 3 has no stake and node 7 isn't a participant.
+        assert_eq!(counts[3], 0);
+        for (id, expected) in [(0, 0.1), (1, 0.2), (2, 0.7)] {
+            let frequency = counts[id] as f64 / epochs as f64;
+            assert!((frequency - expected).abs() < 0.03, "node {} led {}", id, frequency);
+        }
+
+        // Without any stake, all participants can lead.
+        let election = StakeWeightedLeaderElection::default();
+        assert!((0..20).all(|epoch| election.leader(epoch, 4) < 4));
+    }
+}
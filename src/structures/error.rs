@@ -0,0 +1,305 @@
+use std::{fmt, io};
+
+use openssl::error::ErrorStack;
+
+use super::time::TimeSource;
+
+/// This enum represents all errors a node can encounter during the protocol execution.
+#[derive(Debug)]
+pub enum NodeError {
+    /// OpenSSL key generation, signing or verification failure
+    Crypto(ErrorStack),
+    /// System clock verification failure
+    Clock(ClockError),
+    /// Block proposer is not the epoch leader
+    InvalidLeader,
+    /// Signature doesn't match the provided public key
+    SignatureVerificationFailed,
+    /// Block is not known to the node
+    UnknownBlock,
+    /// Block parent is not known to the node
+    UnknownParent,
+    /// Block parent is known, but block doesn't extend a chain tip
+    BlockDoesNotExtend,
+    /// Blockchain contains no blocks
+    EmptyBlockchain,
+    /// Operation is reserved to validator nodes
+    NotValidator,
+    /// Chain doesn't connect to the node checkpoint or genesis block
+    CheckpointMismatch,
+    /// Node id is not a known protocol participant
+    UnknownNode,
+    /// Epoch leader proposed, or a node voted for, two different blocks in the same epoch
+    Equivocation,
+    /// Blockchain is internally inconsistent
+    Validation(ValidationError),
+    /// Chain conflicts with the node finalized blockchain
+    ChainConflict,
+    /// Node initial block is not a valid genesis block
+    InvalidGenesis,
+    /// Node mempool reached its size limit
+    MempoolFull,
+    /// Proposed block epoch isn't the node current epoch
+    WrongEpoch,
+    /// Transaction was rejected by the node transaction validator
+    InvalidTransaction,
+    /// Node already proposed a block for the current or a later epoch
+    AlreadyProposed,
+    /// Message delivery through a transport failed
+    Transport(TransportError),
+    /// Custom quorum ratio has a zero denominator, or can't be reached
+    InvalidQuorum,
+    /// Epoch delta is zero
+    InvalidDelta,
+    /// Finalization depth is lower than 2 blocks
+    InvalidFinalizationDepth,
+    /// Key type doesn't match the signature scheme
+    InvalidKey,
+}
+
+impl fmt::Display for NodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeError::Crypto(e) => write!(f, "Crypto error: {}", e),
+            NodeError::Clock(e) => write!(f, "Clock error: {}", e),
+            NodeError::InvalidLeader => write!(f, "Block proposer is not the epoch leader."),
+            NodeError::SignatureVerificationFailed => write!(f, "Signature verification failed."),
+            NodeError::UnknownBlock => write!(f, "Block is unknown."),
+            NodeError::UnknownParent => write!(f, "Block parent is unknown."),
+            NodeError::BlockDoesNotExtend => write!(f, "Block doesn't extend any known chains."),
+            NodeError::EmptyBlockchain => write!(f, "Blockchain is empty."),
+            NodeError::NotValidator => write!(f, "Node is not a validator."),
+            NodeError::CheckpointMismatch => {
+                write!(f, "Chain doesn't connect to the node checkpoint.")
+            }
+            NodeError::UnknownNode => write!(f, "Node is unknown."),
+            NodeError::Equivocation => write!(f, "Conflicting blocks signed for the same epoch."),
+            NodeError::Validation(e) => write!(f, "Invalid blockchain: {}", e),
+            NodeError::ChainConflict => write!(f, "Chain conflicts with the finalized blockchain."),
+            NodeError::InvalidGenesis => write!(f, "Initial block is not a valid genesis block."),
+            NodeError::MempoolFull => write!(f, "Mempool is full."),
+            NodeError::WrongEpoch => write!(f, "Block epoch isn't the current epoch."),
+            NodeError::InvalidTransaction => write!(f, "Transaction is invalid."),
+            NodeError::AlreadyProposed => write!(f, "Block already proposed for this epoch."),
+            NodeError::InvalidQuorum => write!(f, "Quorum ratio must lie below 1."),
+            NodeError::InvalidDelta => write!(f, "Epoch delta must not be zero."),
+            NodeError::InvalidFinalizationDepth => {
+                write!(f, "Finalization depth must be at least 2 blocks.")
+            }
+            NodeError::InvalidKey => write!(f, "Key doesn't match the signature scheme."),
+            NodeError::Transport(e) => write!(f, "Transport error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for NodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NodeError::Crypto(e) => Some(e),
+            NodeError::Clock(e) => Some(e),
+            NodeError::Validation(e) => Some(e),
+            NodeError::Transport(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ErrorStack> for NodeError {
+    fn from(e: ErrorStack) -> NodeError {
+        NodeError::Crypto(e)
+    }
+}
+
+impl From<ClockError> for NodeError {
+    fn from(e: ClockError) -> NodeError {
+        NodeError::Clock(e)
+    }
+}
+
+impl From<TransportError> for NodeError {
+    fn from(e: TransportError) -> NodeError {
+        NodeError::Transport(e)
+    }
+}
+
+impl From<ValidationError> for NodeError {
+    fn from(e: ValidationError) -> NodeError {
+        NodeError::Validation(e)
+    }
+}
+
+/// This enum represents all errors that can occur while verifying the system clock.
+#[derive(Debug)]
+pub enum ClockError {
+    /// Network failure while polling a time source
+    Io(io::Error),
+    /// TLS connection failure while polling worldtimeapi
+    Tls(String),
+    /// NTP request failure
+    Ntp(String),
+    /// Time source returned a response we couldn't parse
+    InvalidResponse(String),
+    /// Time source didn't answer within the configured timeout
+    Timeout,
+    /// System clock disagrees with the time sources after all retries
+    InvalidClock,
+    /// Polling provided time source failed
+    SourceFailed(TimeSource, Box<ClockError>),
+}
+
+impl ClockError {
+    /// Attributes the error to provided time source.
+    pub fn at_source(self, source: TimeSource) -> ClockError {
+        ClockError::SourceFailed(source, Box::new(self))
+    }
+}
+
+impl fmt::Display for ClockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClockError::Io(e) => write!(f, "IO error: {}", e),
+            ClockError::Tls(e) => write!(f, "TLS error: {}", e),
+            ClockError::Ntp(e) => write!(f, "NTP error: {}", e),
+            ClockError::InvalidResponse(e) => write!(f, "Invalid time source response: {}", e),
+            ClockError::Timeout => write!(f, "Time source timed out."),
+            ClockError::InvalidClock => write!(f, "Invalid system clock."),
+            ClockError::SourceFailed(source, e) => {
+                write!(f, "{} time source failed: {}", source, e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClockError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClockError::Io(e) => Some(e),
+            ClockError::SourceFailed(_, e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Socket timeouts surface as WouldBlock or TimedOut, depending on the platform.
+impl From<io::Error> for ClockError {
+    fn from(e: io::Error) -> ClockError {
+        match e.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => ClockError::Timeout,
+            _ => ClockError::Io(e),
+        }
+    }
+}
+
+/// This enum represents violations of the protocol safety invariants a node can observe.
+#[derive(Debug, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// Finalized chain is shorter than previously observed
+    FinalizedChainShrunk { expected: usize, found: usize },
+    /// A previously finalized block has been altered
+    FinalizedBlockAltered,
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::FinalizedChainShrunk { expected, found } => write!(
+                f,
+                "Finalized chain shrunk: expected at least {} blocks, found {}.",
+                expected, found
+            ),
+            InvariantViolation::FinalizedBlockAltered => {
+                write!(f, "A finalized block has been altered.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvariantViolation {}
+
+/// This enum represents the inconsistencies a blockchain can contain.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// Block parent hash doesn't match the hash of the previous block
+    BrokenLink { index: usize },
+    /// Block epoch doesn't advance past the epoch of the previous block
+    NonIncreasingEpoch { index: usize },
+    /// Block doesn't carry enough valid votes to be notarized
+    InsufficientVotes { index: usize },
+    /// Fork point lies past the end of the parent chain
+    ForkPointOutOfRange { up_to: usize },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::BrokenLink { index } => {
+                write!(f, "Block {} doesn't link to the previous block.", index)
+            }
+            ValidationError::NonIncreasingEpoch { index } => {
+                write!(f, "Block {} epoch doesn't increase.", index)
+            }
+            ValidationError::InsufficientVotes { index } => {
+                write!(f, "Block {} doesn't have enough valid votes.", index)
+            }
+            ValidationError::ForkPointOutOfRange { up_to } => {
+                write!(f, "Fork point {} is past the parent chain end.", up_to)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// This enum represents the reasons a string isn't a valid block hash.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BlockHashError {
+    /// String doesn't have the length of a hex encoded hash
+    InvalidLength(usize),
+    /// String contains non hex characters
+    InvalidHex,
+}
+
+impl fmt::Display for BlockHashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockHashError::InvalidLength(length) => {
+                write!(f, "Block hash has invalid length: {}.", length)
+            }
+            BlockHashError::InvalidHex => write!(f, "Block hash is not hex encoded."),
+        }
+    }
+}
+
+impl std::error::Error for BlockHashError {}
+
+/// This enum represents the failures of delivering a message to another node.
+#[derive(Debug)]
+pub enum TransportError {
+    /// Recipient node is not reachable through the transport
+    UnknownPeer(u64),
+    /// Recipient node stopped receiving messages
+    Disconnected(u64),
+    /// Recipient node rejected the message
+    Rejected { to: u64, error: Box<NodeError> },
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportError::UnknownPeer(id) => write!(f, "Node {} is unreachable.", id),
+            TransportError::Disconnected(id) => write!(f, "Node {} is disconnected.", id),
+            TransportError::Rejected { to, error } => {
+                write!(f, "Node {} rejected the message: {}", to, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TransportError::Rejected { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
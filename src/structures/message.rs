@@ -0,0 +1,12 @@
+use super::vote::Vote;
+
+/// This enum represents the messages nodes exchange over the network.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetworkMessage {
+    /// Block proposal(mapped as Vote) of the epoch leader
+    Proposal(Vote),
+    /// Node vote on a proposed block
+    Vote(Vote),
+    /// Transaction to be included in a block
+    Transaction(String),
+}
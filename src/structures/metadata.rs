@@ -1,27 +1,109 @@
-use std::time::Instant;
+use std::time::Duration;
 
 use super::vote::Vote;
 
 /// This struct represents additional Block information used by the Streamlet consensus protocol.
-#[derive(Debug, Clone)]
+/// Votes and flags are private, so blocks only get finalized after being notarized.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Metadata {
     /// Epoch votes
-    pub votes: Vec<Vote>,
+    votes: Vec<Vote>,
     /// Block notarization flag
-    pub notarized: bool,
+    notarized: bool,
     /// Block finalization flag
-    pub finalized: bool,
-    /// Block creation timestamp
-    pub timestamp: Instant,
+    finalized: bool,
+    /// Block time relative to genesis, set deterministically by the proposer, so nodes
+    /// holding the same block agree on it. None when the block time is unknown.
+    pub timestamp: Option<Duration>,
 }
 
 impl Metadata {
     pub fn new() -> Metadata {
-        Metadata {
-            votes: Vec::new(),
-            notarized: false,
-            finalized: false,
-            timestamp: Instant::now(),
+        Metadata { votes: Vec::new(), notarized: false, finalized: false, timestamp: None }
+    }
+
+    /// Marks the block as notarized.
+    pub fn notarize(&mut self) {
+        self.notarized = true;
+    }
+
+    /// Marks the block as finalized. Only notarized blocks can be finalized,
+    /// so calling it before notarize is a bug and panics.
+    pub fn finalize(&mut self) {
+        assert!(self.notarized, "Block must be notarized before getting finalized.");
+        self.finalized = true;
+    }
+
+    pub fn is_notarized(&self) -> bool {
+        self.notarized
+    }
+
+    pub fn is_finalized(&self) -> bool {
+        self.finalized
+    }
+
+    /// Appends provided vote, unless a vote with the same intent, i.e. from the same sender
+    /// for the same block, is already held. Returns whether the vote was added.
+    pub fn add_vote(&mut self, vote: Vote) -> bool {
+        if self.votes.iter().any(|v| v.same_intent(&vote)) {
+            return false
         }
+        self.votes.push(vote);
+        true
+    }
+
+    /// Votes held for the block.
+    pub fn votes(&self) -> &[Vote] {
+        &self.votes
+    }
+
+    /// Drops all held votes, e.g. once they are carried by a certificate instead.
+    pub fn clear_votes(&mut self) {
+        self.votes.clear();
+    }
+
+    /// Number of distinct nodes that voted.
+    pub fn vote_count(&self) -> usize {
+        self.votes.len()
+    }
+}
+
+impl Default for Metadata {
+    fn default() -> Self {
+        Metadata::new()
+    }
+}
+
+impl_serde_struct!(Metadata {
+    votes: Vec<Vote>,
+    notarized: bool,
+    finalized: bool,
+    timestamp: Option<Duration>,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::Metadata;
+    use crate::structures::{block::Block, vote::Vote};
+
+    #[test]
+    fn votes_and_flags() {
+        let block = Block::genesis();
+        let mut metadata = Metadata::new();
+        assert!(metadata.add_vote(Vote::new(vec![1], block.clone(), 0)));
+        assert!(!metadata.add_vote(Vote::new(vec![2], block.clone(), 0)));
+        assert!(metadata.add_vote(Vote::new(vec![3], block, 1)));
+        assert_eq!(metadata.vote_count(), 2);
+
+        assert!(!metadata.is_notarized());
+        metadata.notarize();
+        metadata.finalize();
+        assert!(metadata.is_notarized() && metadata.is_finalized());
+    }
+
+    #[test]
+    #[should_panic(expected = "notarized before getting finalized")]
+    fn finalize_requires_notarization() {
+        Metadata::new().finalize();
     }
 }
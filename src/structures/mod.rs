@@ -2,16 +2,56 @@
 //!
 //! A library for modeling consensus algorithm structures.
 
+#[macro_use]
+mod serialization;
+
+pub mod audit;
 pub mod block;
 pub mod blockchain;
+pub mod certificate;
+pub mod crypto;
+#[cfg(feature = "pure-ed25519")]
+mod ed25519;
+pub mod election;
+pub mod error;
+pub mod message;
 pub mod metadata;
 pub mod node;
+pub mod registry;
+#[cfg(test)]
+mod test_utils;
 pub mod time;
+pub mod transaction;
+pub mod transport;
 pub mod vote;
 
-pub use block::Block;
+pub use audit::verify_finalized_chain;
+pub use block::{Block, BlockHash};
 pub use blockchain::Blockchain;
+pub use certificate::NotarizationCertificate;
+#[cfg(feature = "pure-ed25519")]
+pub use crypto::PureEd25519Scheme;
+pub use crypto::{scheme_for_key, Ed25519Scheme, RsaSha256Scheme, SignatureScheme};
+pub use election::{
+    HashLeaderElection, LeaderElection, RoundRobinLeaderElection, StakeWeightedLeaderElection,
+};
+pub use error::{
+    BlockHashError, ClockError, InvariantViolation, NodeError, TransportError, ValidationError,
+};
+pub use message::NetworkMessage;
 pub use metadata::Metadata;
-pub use node::Node;
-pub use time::check_clock;
-pub use vote::Vote;
+pub use node::{ForkInfo, MempoolPolicy, Node, NodeRole, QuorumRatio, QuorumRule};
+pub use registry::NodeRegistry;
+#[cfg(any(test, feature = "testing"))]
+pub use time::TestClock;
+#[cfg(feature = "clock")]
+pub use time::{
+    check_clock, check_clock_async, check_clock_with, NetworkClockSource, DEFAULT_CLOCK_TIMEOUT,
+};
+pub use time::{
+    Clock, ClockCheckMode, ClockOffset, ClockSource, DefaultClockSource, SystemClock,
+    SystemClockSource, TimeSource, DEFAULT_CLOCK_TOLERANCE_SECS,
+};
+pub use transaction::{AcceptAllTransactions, TransactionValidator};
+pub use transport::{ChannelTransport, LocalTransport, Transport};
+pub use vote::{Vote, VoteOutcome, PROPOSAL_DOMAIN, VOTE_DOMAIN};
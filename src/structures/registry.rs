@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use openssl::{
+    hash::{hash, MessageDigest},
+    pkey::{HasPublic, PKey, Public},
+};
+
+use super::{crypto::public_key, error::NodeError};
+
+/// This struct represents the protocol membership, as known by a node.
+/// It holds the public key of each participating node, by node id.
+#[derive(Debug, Clone, Default)]
+pub struct NodeRegistry {
+    pub nodes: HashMap<u64, PKey<Public>>,
+}
+
+impl NodeRegistry {
+    pub fn new() -> NodeRegistry {
+        NodeRegistry { nodes: HashMap::new() }
+    }
+
+    /// Registers provided node public key, replacing any previously registered one.
+    pub fn register<T: HasPublic>(&mut self, id: u64, key: &PKey<T>) -> Result<(), NodeError> {
+        self.nodes.insert(id, public_key(key)?);
+        Ok(())
+    }
+
+    /// Public key registered for provided node id, if any.
+    pub fn get(&self, id: u64) -> Option<&PKey<Public>> {
+        self.nodes.get(&id)
+    }
+
+    /// Registered node ids, in ascending order.
+    pub fn ids(&self) -> Vec<u64> {
+        let mut ids: Vec<u64> = self.nodes.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Number of registered nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Hex encoded SHA-256 digest identifying the membership view, computed over the
+    /// registered ids and public keys in ascending id order. Nodes holding the same
+    /// participants share the same membership hash.
+    pub fn membership_hash(&self) -> Result<String, NodeError> {
+        let mut encoding = Vec::new();
+        for id in self.ids() {
+            let key = self.nodes[&id].public_key_to_der()?;
+            encoding.extend_from_slice(&id.to_be_bytes());
+            encoding.extend_from_slice(&(key.len() as u64).to_be_bytes());
+            encoding.extend_from_slice(&key);
+        }
+        let digest = hash(MessageDigest::sha256(), &encoding)?;
+        Ok(digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{pkey::PKey, rsa::Rsa};
+
+    use super::NodeRegistry;
+
+    #[test]
+    fn register_and_lookup() {
+        let keypair = PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap();
+        let mut registry = NodeRegistry::new();
+        assert!(registry.is_empty());
+
+        registry.register(0, &keypair).unwrap();
+        registry.register(0, &keypair).unwrap();
+        assert_eq!(registry.len(), 1);
+        assert!(registry.get(0).unwrap().public_eq(&keypair));
+        assert!(registry.get(1).is_none());
+        registry.register(3, &keypair).unwrap();
+        assert_eq!(registry.ids(), vec![0, 3]);
+    }
+
+    #[test]
+    fn membership_hash_identifies_participants() {
+        let keypair0 = PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap();
+        let keypair1 = PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap();
+        let mut registry0 = NodeRegistry::new();
+        registry0.register(0, &keypair0).unwrap();
+        registry0.register(1, &keypair1).unwrap();
+        // Registration order doesn't matter.
+        let mut registry1 = NodeRegistry::new();
+        registry1.register(1, &keypair1).unwrap();
+        registry1.register(0, &keypair0).unwrap();
+        let membership_hash = registry0.membership_hash().unwrap();
+        assert_eq!(membership_hash.len(), 64);
+        assert_eq!(registry1.membership_hash().unwrap(), membership_hash);
+
+        registry1.register(1, &keypair0).unwrap();
+        assert_ne!(registry1.membership_hash().unwrap(), membership_hash);
+        registry0.register(2, &keypair1).unwrap();
+        assert_ne!(registry0.membership_hash().unwrap(), membership_hash);
+        assert_ne!(NodeRegistry::new().membership_hash().unwrap(), membership_hash);
+    }
+}
@@ -0,0 +1,69 @@
+//! Serialization support for the protocol structures.
+
+/// Implements serde Serialize and Deserialize for a struct with named fields,
+/// (de)serializing it as a struct of all listed fields.
+/// Fields listed after `skip` aren't (de)serialized, and get their default value.
+macro_rules! impl_serde_struct {
+    ($name:ident { $($field:ident: $ty:ty),* $(,)? }) => {
+        impl_serde_struct!($name { $($field: $ty),* } skip {});
+    };
+    ($name:ident { $($field:ident: $ty:ty),* $(,)? } skip { $($skipped:ident),* $(,)? }) => {
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use serde::ser::SerializeStruct;
+                let fields = [$(stringify!($field)),*];
+                let mut state = serializer.serialize_struct(stringify!($name), fields.len())?;
+                $(state.serialize_field(stringify!($field), &self.$field)?;)*
+                state.end()
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                use serde::de::{Error, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+                struct StructVisitor;
+
+                impl<'de> Visitor<'de> for StructVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(f, "struct {}", stringify!($name))
+                    }
+
+                    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<$name, A::Error> {
+                        let mut index = 0;
+                        $(
+                            let $field: $ty = seq
+                                .next_element()?
+                                .ok_or_else(|| A::Error::invalid_length(index, &self))?;
+                            index += 1;
+                        )*
+                        let _ = index;
+                        Ok($name { $($field,)* $($skipped: Default::default(),)* })
+                    }
+
+                    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<$name, A::Error> {
+                        $(let mut $field: Option<$ty> = None;)*
+                        while let Some(key) = map.next_key::<String>()? {
+                            match key.as_str() {
+                                $(stringify!($field) => $field = Some(map.next_value()?),)*
+                                _ => {
+                                    map.next_value::<IgnoredAny>()?;
+                                }
+                            }
+                        }
+                        $(
+                            let $field =
+                                $field.ok_or_else(|| A::Error::missing_field(stringify!($field)))?;
+                        )*
+                        Ok($name { $($field,)* $($skipped: Default::default(),)* })
+                    }
+                }
+
+                const FIELDS: &[&str] = &[$(stringify!($field)),*];
+                deserializer.deserialize_struct(stringify!($name), FIELDS, StructVisitor)
+            }
+        }
+    };
+}
@@ -0,0 +1,67 @@
+//! Test fixtures shared by the structures modules tests.
+
+use std::{collections::HashMap, time::SystemTime};
+
+use openssl::pkey::{PKey, Private, Public};
+
+use super::{
+    block::Block,
+    crypto::{public_key, scheme_for_key},
+    node::{Node, NodeRole},
+    time::SystemClockSource,
+    vote::{Vote, VOTE_DOMAIN},
+};
+
+/// Generates provided number of Ed25519 keypairs, which are fast to produce.
+pub(crate) fn keypairs(count: usize) -> Vec<PKey<Private>> {
+    (0..count).map(|_| PKey::generate_ed25519().unwrap()).collect()
+}
+
+/// Public keys of provided keypairs, by node id, i.e. their index.
+pub(crate) fn public_keys(keypairs: &[PKey<Private>]) -> HashMap<u64, PKey<Public>> {
+    keypairs.iter().enumerate().map(|(id, k)| (id as u64, public_key(k).unwrap())).collect()
+}
+
+/// Vote of provided node id for provided block, signed with the scheme matching the key.
+pub(crate) fn sign_vote(keypair: &PKey<Private>, block: &Block, id: u64) -> Vote {
+    let payload = Vote::signing_payload(VOTE_DOMAIN, block, id);
+    Vote::new(scheme_for_key(keypair).sign(keypair, &payload).unwrap(), block.clone(), id)
+}
+
+/// Validator node signing with an Ed25519 keypair, trusting the system clock as is.
+/// It knows no peers.
+pub(crate) fn test_node(id: u64) -> Node {
+    let keypair = keypairs(1).remove(0);
+    Node::with_keypair_and_clock_source(
+        id,
+        SystemTime::now(),
+        Block::genesis(),
+        keypair,
+        &SystemClockSource,
+    )
+    .unwrap()
+}
+
+/// Registers the validators public keys on every node.
+pub(crate) fn register_validators(nodes: &mut [Node]) {
+    let keys: Vec<_> = nodes
+        .iter()
+        .filter(|node| node.role == NodeRole::Validator)
+        .map(|node| (node.id, node.public_key().unwrap()))
+        .collect();
+    for node in nodes.iter_mut() {
+        for (id, public_key) in &keys {
+            node.register_peer(*id, public_key).unwrap();
+        }
+    }
+}
+
+/// Creates provided number of validators, sharing a genesis time and knowing each other.
+pub(crate) fn test_nodes(count: u64, genesis_time: SystemTime) -> Vec<Node> {
+    let mut nodes: Vec<Node> = (0..count).map(test_node).collect();
+    for node in nodes.iter_mut() {
+        node.genesis_time = genesis_time;
+    }
+    register_validators(&mut nodes);
+    nodes
+}
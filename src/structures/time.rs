@@ -1,93 +1,775 @@
-use native_tls::TlsConnector;
 use std::{
-    io::{Read, Write},
-    net::TcpStream,
-    time::{Instant, SystemTime},
+    fmt,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+    time::SystemTime,
 };
 
+#[cfg(any(test, feature = "testing", feature = "clock"))]
+use std::time::Duration;
+
+#[cfg(feature = "clock")]
+use log::{debug, info, warn};
+#[cfg(feature = "clock")]
+use native_tls::{HandshakeError, TlsConnector};
+#[cfg(feature = "clock")]
 use serde_json::Value;
+#[cfg(feature = "clock")]
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Instant,
+};
+
+use super::error::ClockError;
 
 // Clock sync parameters
+#[cfg(feature = "clock")]
 const RETRIES: u8 = 10;
+// Delay before the first retry, doubling after each one up to the max
+#[cfg(feature = "clock")]
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+#[cfg(feature = "clock")]
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(5);
+#[cfg(feature = "clock")]
 const WORLDTIMEAPI_ADDRESS: &str = "worldtimeapi.org";
+#[cfg(feature = "clock")]
 const WORLDTIMEAPI_ADDRESS_WITH_PORT: &str = "worldtimeapi.org:443";
-const WORLDTIMEAPI_PAYLOAD: &[u8; 88] = b"GET /api/timezone/Etc/UTC HTTP/1.1\r\nHost: worldtimeapi.org\r\nAccept: application/json\r\n\r\n";
+#[cfg(feature = "clock")]
+const WORLDTIMEAPI_PAYLOAD: &[u8] = b"GET /api/timezone/Etc/UTC HTTP/1.1\r\nHost: worldtimeapi.org\r\nAccept: application/json\r\nConnection: close\r\n\r\n";
+// Largest worldtimeapi response accepted, the actual ones are below 1KiB
+#[cfg(feature = "clock")]
+const MAX_RESPONSE_BYTES: u64 = 64 * 1024;
+#[cfg(feature = "clock")]
 const NTP_ADDRESS: &str = "0.pool.ntp.org:123";
+#[cfg(feature = "clock")]
 const EPOCH: u64 = 2208988800; //1900
 
+/// Default accepted difference between the system clock and the time sources, in seconds.
+pub const DEFAULT_CLOCK_TOLERANCE_SECS: u64 = 2;
+
+/// Default time allowed for connecting to, writing to and reading from a time source.
+#[cfg(feature = "clock")]
+pub const DEFAULT_CLOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// This enum represents the time sources the system clock is checked against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeSource {
+    /// worldtimeapi.org HTTP API
+    WorldTimeApi,
+    /// NTP pool
+    Ntp,
+}
+
+impl fmt::Display for TimeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeSource::WorldTimeApi => write!(f, "worldtimeapi"),
+            TimeSource::Ntp => write!(f, "ntp"),
+        }
+    }
+}
+
+/// This enum represents which time sources the system clock check polls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClockCheckMode {
+    /// Both worldtimeapi and ntp must agree with the system clock
+    #[default]
+    Both,
+    /// Only ntp is polled, for users distrusting the HTTP API
+    NtpOnly,
+    /// Only worldtimeapi is polled, for users behind firewalls blocking ntp
+    ApiOnly,
+}
+
+impl ClockCheckMode {
+    /// Checks if provided time source is polled under this mode.
+    pub fn polls(&self, source: TimeSource) -> bool {
+        match self {
+            ClockCheckMode::Both => true,
+            ClockCheckMode::NtpOnly => source == TimeSource::Ntp,
+            ClockCheckMode::ApiOnly => source == TimeSource::WorldTimeApi,
+        }
+    }
+}
+
+/// This struct represents the measured difference between the system clock and each
+/// polled time source, in seconds. Positive values mean the system clock is ahead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockOffset {
+    /// System time minus worldtimeapi time, if polled
+    pub worldtimeapi: Option<i64>,
+    /// System time minus ntp time, if polled
+    pub ntp: Option<i64>,
+}
+
+impl ClockOffset {
+    /// System clock is considered valid when it agrees with all polled time sources,
+    /// within provided tolerance.
+    pub fn is_within(&self, tolerance_secs: u64) -> bool {
+        [self.worldtimeapi, self.ntp]
+            .into_iter()
+            .flatten()
+            .all(|offset| offset.unsigned_abs() <= tolerance_secs)
+    }
+
+    /// System clock check, using the default tolerance.
+    pub fn is_synced(&self) -> bool {
+        self.is_within(DEFAULT_CLOCK_TOLERANCE_SECS)
+    }
+}
+
+/// This trait represents a source used to verify the system clock before a node starts.
+pub trait ClockSource {
+    /// Verifies the system clock, returning the measured offset, if the source measures one.
+    fn verify(&self) -> Result<Option<ClockOffset>, ClockError>;
+}
+
+/// Clock source polling worldtimeapi and/or ntp over the network, using check_clock_with.
+#[cfg(feature = "clock")]
+#[derive(Debug, Clone, Copy)]
+pub struct NetworkClockSource {
+    /// Time sources to poll
+    pub mode: ClockCheckMode,
+    /// Accepted difference between the system clock and the time sources, in seconds
+    pub tolerance_secs: u64,
+    /// Time allowed for each network operation with worldtimeapi.
+    /// Ntp requests use the fixed timeout of the ntp crate.
+    pub timeout: Duration,
+}
+
+#[cfg(feature = "clock")]
+impl NetworkClockSource {
+    /// Checks the system clock against the configured time sources, retrying with
+    /// backoff until the offset is within tolerance.
+    pub fn check(&self) -> Result<ClockOffset, ClockError> {
+        let check = || clock_check(self.mode, self.timeout);
+        retry_clock_check(check, self.tolerance_secs, RETRIES, RETRY_BACKOFF)
+    }
+}
+
+#[cfg(feature = "clock")]
+impl Default for NetworkClockSource {
+    fn default() -> NetworkClockSource {
+        NetworkClockSource {
+            mode: ClockCheckMode::Both,
+            tolerance_secs: DEFAULT_CLOCK_TOLERANCE_SECS,
+            timeout: DEFAULT_CLOCK_TIMEOUT,
+        }
+    }
+}
+
+#[cfg(feature = "clock")]
+impl ClockSource for NetworkClockSource {
+    fn verify(&self) -> Result<Option<ClockOffset>, ClockError> {
+        self.check().map(Some)
+    }
+}
+
+/// Clock source trusting the system clock as is, without any network access.
+/// Useful for offline usage and tests.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClockSource;
+
+impl ClockSource for SystemClockSource {
+    fn verify(&self) -> Result<Option<ClockOffset>, ClockError> {
+        Ok(None)
+    }
+}
+
+/// Clock source node constructors verify the system clock with, unless one is provided:
+/// the network check when the clock feature is enabled, otherwise the system clock is
+/// trusted as is.
+#[cfg(feature = "clock")]
+pub type DefaultClockSource = NetworkClockSource;
+
+/// Clock source node constructors verify the system clock with, unless one is provided:
+/// the network check when the clock feature is enabled, otherwise the system clock is
+/// trusted as is.
+#[cfg(not(feature = "clock"))]
+pub type DefaultClockSource = SystemClockSource;
+
+/// This trait represents the wall-clock time nodes read to track epochs.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Current wall-clock time.
+    fn now(&self) -> SystemTime;
+}
+
+/// Clock reading the system time. Default node clock.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Deterministic clock for tests, whose time only moves when set or advanced.
+/// Clones share the same time, so a single clock can drive multiple nodes.
+#[cfg(any(test, feature = "testing"))]
+#[derive(Debug, Clone)]
+pub struct TestClock {
+    now: Arc<Mutex<SystemTime>>,
+    /// Genesis time of the driven nodes
+    pub genesis_time: SystemTime,
+    /// Epoch duration parameter of the driven nodes
+    pub delta: u64,
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl TestClock {
+    /// Creates a clock standing at provided genesis time, for nodes using provided delta.
+    pub fn new(genesis_time: SystemTime, delta: u64) -> TestClock {
+        TestClock { now: Arc::new(Mutex::new(genesis_time)), genesis_time, delta }
+    }
+
+    /// Sets the current time.
+    pub fn set(&self, now: SystemTime) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    /// Moves the current time forward by provided duration.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) += duration;
+    }
+
+    /// Places the current time in the middle of provided epoch, away from its boundaries.
+    pub fn set_epoch(&self, epoch: u64) {
+        self.set(self.genesis_time + Duration::from_secs(epoch * 2 * self.delta + self.delta));
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl Clock for TestClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
 // Raw https request execution for worldtimeapi
-fn worldtimeapi_request() -> Value {
+#[cfg(feature = "clock")]
+fn worldtimeapi_request(timeout: Duration) -> Result<Value, ClockError> {
     // Create connection
-    let connector = TlsConnector::new().unwrap();
-    let stream = TcpStream::connect(WORLDTIMEAPI_ADDRESS_WITH_PORT).unwrap();
-    let mut stream = connector.connect(WORLDTIMEAPI_ADDRESS, stream).unwrap();
-    stream.write_all(WORLDTIMEAPI_PAYLOAD).unwrap();
+    let connector = TlsConnector::new().map_err(|e| ClockError::Tls(e.to_string()))?;
+    let stream = connect(WORLDTIMEAPI_ADDRESS_WITH_PORT, timeout)?;
+    let mut stream = connector.connect(WORLDTIMEAPI_ADDRESS, stream).map_err(|e| match e {
+        // Handshake only blocks when the socket timed out
+        HandshakeError::WouldBlock(_) => ClockError::Timeout,
+        HandshakeError::Failure(e) => ClockError::Tls(e.to_string()),
+    })?;
 
     // Execute request
-    let mut res = vec![0_u8; 1024];
-    stream.read(&mut res).unwrap();
+    json_request(&mut stream, WORLDTIMEAPI_PAYLOAD)
+}
+
+// Opens a TCP connection to provided address, trying each resolved address in turn.
+// Connecting, reading and writing all fail once provided timeout elapses.
+#[cfg(feature = "clock")]
+fn connect<A: ToSocketAddrs>(address: A, timeout: Duration) -> Result<TcpStream, ClockError> {
+    let mut last_error = io::Error::new(ErrorKind::NotFound, "address didn't resolve");
+    for address in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(stream) => {
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))?;
+                return Ok(stream)
+            }
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error.into())
+}
+
+// Sends a raw HTTP request over provided stream and parses the JSON body of the response.
+// Request must ask the server to close the connection, as the response is read until EOF.
+#[cfg(feature = "clock")]
+fn json_request<S: Read + Write>(stream: &mut S, payload: &[u8]) -> Result<Value, ClockError> {
+    stream.write_all(payload)?;
+    let response = read_response(stream)?;
 
     // Parse response
-    let reply = String::from_utf8(res).unwrap();
-    let lines = reply.split('\n');
-    // JSON data exist in last row of response
-    let last = lines.last().unwrap().trim_matches(char::from(0));
-    println!("worldtimeapi json response: {:#?}", last);
-    let reply = serde_json::from_str(last).unwrap();
+    let body = response_body(&response)?;
+    debug!("worldtimeapi json response: {:#?}", body);
+    serde_json::from_str(&body).map_err(|e| ClockError::InvalidResponse(e.to_string()))
+}
+
+// Reads the full response, until the server closes the connection.
+// Some servers close TLS connections without notifying, so an unexpected EOF
+// also marks the end of the response.
+// Responses larger than MAX_RESPONSE_BYTES are rejected.
+#[cfg(feature = "clock")]
+fn read_response<R: Read>(reader: &mut R) -> Result<Vec<u8>, ClockError> {
+    let mut reader = reader.take(MAX_RESPONSE_BYTES + 1);
+    let mut response = Vec::new();
+    let mut buf = [0_u8; 1024];
+    loop {
+        if response.len() as u64 > MAX_RESPONSE_BYTES {
+            return Err(ClockError::InvalidResponse(String::from("response too large")))
+        }
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(read) => response.extend_from_slice(&buf[..read]),
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof && !response.is_empty() => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(response)
+}
 
-    reply
+// Extracts the body of an HTTP response, found after the blank line ending the headers.
+// Chunked transfer encoding is decoded.
+#[cfg(feature = "clock")]
+fn response_body(response: &[u8]) -> Result<String, ClockError> {
+    let response =
+        std::str::from_utf8(response).map_err(|e| ClockError::InvalidResponse(e.to_string()))?;
+    let (headers, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| ClockError::InvalidResponse(String::from("missing headers end")))?;
+    let chunked = headers.lines().any(|header| {
+        let header = header.to_ascii_lowercase();
+        header.starts_with("transfer-encoding:") && header.contains("chunked")
+    });
+    if !chunked {
+        return Ok(body.to_string())
+    }
+
+    let mut decoded = String::new();
+    let mut rest = body;
+    loop {
+        let (size, data) = rest
+            .split_once("\r\n")
+            .ok_or_else(|| ClockError::InvalidResponse(String::from("missing chunk size")))?;
+        // Chunk extensions, if any, follow the size after a semicolon
+        let size = size.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|e| ClockError::InvalidResponse(e.to_string()))?;
+        if size == 0 {
+            break
+        }
+        let chunk = data
+            .get(..size)
+            .ok_or_else(|| ClockError::InvalidResponse(String::from("truncated chunk")))?;
+        decoded.push_str(chunk);
+        rest = data[size..].strip_prefix("\r\n").unwrap_or(&data[size..]);
+    }
+    Ok(decoded)
+}
+
+// This is a very simple check to verify that system time is correct, polling both
+// time sources, using the default tolerance.
+#[cfg(feature = "clock")]
+pub fn check_clock() -> Result<ClockOffset, ClockError> {
+    check_clock_with(ClockCheckMode::Both, DEFAULT_CLOCK_TOLERANCE_SECS)
 }
 
-// This is a very simple check to verify that system time is correct.
-// Retry loop is used to in case discrepancies are found.
+// Only the time sources of provided mode are polled.
+// Retry loop is used to in case discrepancies larger than provided tolerance are found,
+// backing off exponentially between attempts.
 // If all retries fail, system clock is considered invalid.
-pub fn check_clock() {
-    println!("System clock check started...");
-    let mut r = 0;
-    while r < RETRIES {
-        if !clock_check() {
-            println!("Error during clock check, retrying...");
-            r += 1;
-            continue
+// On success, the measured offset of the last check is returned.
+#[cfg(feature = "clock")]
+pub fn check_clock_with(
+    mode: ClockCheckMode,
+    tolerance_secs: u64,
+) -> Result<ClockOffset, ClockError> {
+    NetworkClockSource { mode, tolerance_secs, timeout: DEFAULT_CLOCK_TIMEOUT }.check()
+}
+
+// Runs provided check up to max_attempts times, until its offset is within provided
+// tolerance. Failing checks, e.g. on network errors, are retried as well.
+// Delay between attempts starts at backoff and doubles after each retry.
+// Once all attempts are exhausted, the last check error is returned, or InvalidClock if
+// the last check measured an offset out of tolerance.
+#[cfg(feature = "clock")]
+fn retry_clock_check<F>(
+    mut check: F,
+    tolerance_secs: u64,
+    max_attempts: u8,
+    backoff: Duration,
+) -> Result<ClockOffset, ClockError>
+where
+    F: FnMut() -> Result<ClockOffset, ClockError>,
+{
+    info!("System clock check started...");
+    let mut delay = backoff;
+    let mut last_error = ClockError::InvalidClock;
+    for r in 0..max_attempts {
+        if r > 0 {
+            warn!("Error during clock check: {}, retrying in {:?}...", last_error, delay);
+            thread::sleep(delay);
+            delay = (delay * 2).min(MAX_RETRY_BACKOFF);
+        }
+        last_error = match check() {
+            Ok(offset) if offset.is_within(tolerance_secs) => {
+                info!("System clock check finished. Retries: {:#?}", r);
+                return Ok(offset)
+            }
+            Ok(_) => ClockError::InvalidClock,
+            Err(e) => e,
         };
-        break
     }
+    info!("System clock check failed after {} attempts.", max_attempts);
+    Err(last_error)
+}
+
+// Same check as check_clock, offloaded to a newly spawned thread, so async callers don't block
+// their executor while the time sources are polled. The network I/O itself stays blocking,
+// and each call occupies an OS thread until the check finishes. Works with any executor.
+#[cfg(feature = "clock")]
+pub async fn check_clock_async() -> Result<ClockOffset, ClockError> {
+    BlockingTask::spawn(check_clock).await
+}
+
+/// Future running a blocking closure on a newly spawned thread, resolving to its output.
+/// It offloads blocking work rather than performing non-blocking I/O, so every task holds
+/// an OS thread until the closure returns.
+/// A panic inside the closure is propagated to the task awaiting it.
+pub(crate) struct BlockingTask<T> {
+    state: Arc<Mutex<BlockingTaskState<T>>>,
+}
+
+struct BlockingTaskState<T> {
+    output: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
 
-    println!("System clock check finished. Retries: {:#?}", r);
-    match r {
-        RETRIES => panic!("Invalid system clock."),
-        _ => (),
+impl<T: Send + 'static> BlockingTask<T> {
+    pub(crate) fn spawn<F: FnOnce() -> T + Send + 'static>(f: F) -> BlockingTask<T> {
+        let state = Arc::new(Mutex::new(BlockingTaskState { output: None, waker: None }));
+        let thread_state = Arc::clone(&state);
+        thread::spawn(move || {
+            let output = panic::catch_unwind(AssertUnwindSafe(f));
+            let mut state = thread_state.lock().unwrap_or_else(|e| e.into_inner());
+            state.output = Some(output);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        BlockingTask { state }
     }
 }
 
-fn clock_check() -> bool {
-    // Start elapsed time counter to cover for all requests and processing time
-    let requests_start = Instant::now();
-    // Poll worldtimeapi.org for current UTC timestamp
-    let worldtimeapi_response = worldtimeapi_request();
+impl<T> Future for BlockingTask<T> {
+    type Output = T;
 
-    // Start elapsed time counter to cover for ntp request and processing time
-    let ntp_request_start = Instant::now();
-    // Poll ntp.org for current timestamp
-    let ntp_response: ntp::packet::Packet = ntp::request(NTP_ADDRESS).unwrap();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.output.take() {
+            Some(Ok(output)) => Poll::Ready(output),
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Minimal executor driving provided future to completion on the current thread.
+#[cfg(test)]
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(thread::Thread);
+
+    impl std::task::Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
 
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output
+        }
+        thread::park();
+    }
+}
+
+// Poll worldtimeapi.org for current UTC timestamp
+#[cfg(feature = "clock")]
+fn worldtimeapi_time(timeout: Duration) -> Result<u64, ClockError> {
+    let worldtimeapi_response = worldtimeapi_request(timeout)?;
     // Extract worldtimeapi timestamp from json
-    let mut worldtimeapi_time = worldtimeapi_response["unixtime"].as_u64().unwrap();
+    worldtimeapi_response["unixtime"]
+        .as_u64()
+        .ok_or_else(|| ClockError::InvalidResponse(String::from("missing unixtime")))
+}
 
+// Poll ntp.org for current timestamp
+#[cfg(feature = "clock")]
+fn ntp_time() -> Result<u64, ClockError> {
+    let ntp_response: ntp::packet::Packet =
+        ntp::request(NTP_ADDRESS).map_err(|e| ClockError::Ntp(e.to_string()))?;
     // Remove 1900 epoch to reach UTC timestamp for ntp timestamp
-    let mut ntp_time = ntp_response.transmit_time.sec as u64 - EPOCH;
+    Ok(ntp_response.transmit_time.sec as u64 - EPOCH)
+}
+
+#[cfg(feature = "clock")]
+fn clock_check(mode: ClockCheckMode, timeout: Duration) -> Result<ClockOffset, ClockError> {
+    // Start elapsed time counter to cover for all requests and processing time
+    let requests_start = Instant::now();
+    let worldtimeapi_time = if mode.polls(TimeSource::WorldTimeApi) {
+        Some(worldtimeapi_time(timeout).map_err(|e| e.at_source(TimeSource::WorldTimeApi))?)
+    } else {
+        None
+    };
+
+    // Start elapsed time counter to cover for ntp request and processing time
+    let ntp_request_start = Instant::now();
+    let ntp_time = if mode.polls(TimeSource::Ntp) {
+        Some(ntp_time().map_err(|e| e.at_source(TimeSource::Ntp))?)
+    } else {
+        None
+    };
 
     // Add elapsed time to respone times
-    ntp_time += ntp_request_start.elapsed().as_secs();
-    worldtimeapi_time += requests_start.elapsed().as_secs();
+    let ntp_time = ntp_time.map(|time| time + ntp_request_start.elapsed().as_secs());
+    let worldtimeapi_time = worldtimeapi_time.map(|time| time + requests_start.elapsed().as_secs());
 
     // Current system time
     let system_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
 
-    println!("worldtimeapi_time: {:#?}", worldtimeapi_time);
-    println!("ntp_time: {:#?}", ntp_time);
-    println!("system_time: {:#?}", system_time);
+    debug!("worldtimeapi_time: {:#?}", worldtimeapi_time);
+    debug!("ntp_time: {:#?}", ntp_time);
+    debug!("system_time: {:#?}", system_time);
+
+    // We measure system time offset against the polled time sources
+    Ok(ClockOffset {
+        worldtimeapi: worldtimeapi_time.map(|time| system_time as i64 - time as i64),
+        ntp: ntp_time.map(|time| system_time as i64 - time as i64),
+    })
+}
 
-    // We verify that system time is equal to worldtimeapi and ntp
-    (system_time == worldtimeapi_time) && (system_time == ntp_time)
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "clock")]
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+    };
+    use std::{
+        thread,
+        time::{Duration, SystemTime},
+    };
+
+    use super::{
+        block_on, BlockingTask, Clock, ClockCheckMode, ClockOffset, TestClock, TimeSource,
+        DEFAULT_CLOCK_TOLERANCE_SECS,
+    };
+    #[cfg(feature = "clock")]
+    use super::{
+        connect, json_request, read_response, response_body, retry_clock_check, MAX_RESPONSE_BYTES,
+        RETRIES, WORLDTIMEAPI_PAYLOAD,
+    };
+    use crate::structures::error::ClockError;
+
+    /// Reader handing out its data in small pieces, like a network stream.
+    #[cfg(feature = "clock")]
+    struct PiecewiseReader {
+        data: Vec<u8>,
+        position: usize,
+        piece: usize,
+    }
+
+    #[cfg(feature = "clock")]
+    impl Read for PiecewiseReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let end = (self.position + self.piece).min(self.data.len());
+            let read = (end - self.position).min(buf.len());
+            buf[..read].copy_from_slice(&self.data[self.position..self.position + read]);
+            self.position += read;
+            Ok(read)
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "clock")]
+    fn chunked_response_body_extraction() {
+        let body = r#"{"abbreviation":"UTC","unixtime":1700000000}"#;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+             Transfer-Encoding: chunked\r\n\r\n{:x}\r\n{}\r\n{:x}\r\n{}\r\n0\r\n\r\n",
+            20,
+            &body[..20],
+            body.len() - 20,
+            &body[20..]
+        );
+        let mut reader = PiecewiseReader { data: response.into_bytes(), position: 0, piece: 7 };
+
+        let response = read_response(&mut reader).unwrap();
+        assert_eq!(response_body(&response).unwrap(), body);
+
+        // Reading stops at the size cap.
+        let oversized = vec![b'0'; MAX_RESPONSE_BYTES as usize + 1];
+        let mut reader = PiecewiseReader { data: oversized, position: 0, piece: 4096 };
+        assert!(matches!(read_response(&mut reader), Err(ClockError::InvalidResponse(_))));
+        assert_eq!(reader.position as u64, MAX_RESPONSE_BYTES + 1);
+        let capped = vec![b'0'; MAX_RESPONSE_BYTES as usize];
+        let mut reader = PiecewiseReader { data: capped, position: 0, piece: 4096 };
+        assert_eq!(read_response(&mut reader).unwrap().len() as u64, MAX_RESPONSE_BYTES);
+
+        let plain = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+        assert_eq!(response_body(plain.as_bytes()).unwrap(), body);
+        assert!(response_body(b"HTTP/1.1 200 OK\r\n").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "clock")]
+    fn json_request_against_local_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        // Mock server answers with a chunked body, then closes the connection.
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0_u8; 256];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+                      c\r\n{\"unixtime\":\r\nc\r\n 1700000000}\r\n0\r\n\r\n",
+                )
+                .unwrap();
+            request
+        });
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        let response = json_request(&mut stream, WORLDTIMEAPI_PAYLOAD).unwrap();
+        assert_eq!(response["unixtime"].as_u64(), Some(1700000000));
+
+        let request = String::from_utf8(server.join().unwrap()).unwrap();
+        assert!(request.starts_with("GET /api/timezone/Etc/UTC HTTP/1.1\r\n"));
+        assert!(request.contains("Connection: close\r\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "clock")]
+    fn stalled_server_times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        // Mock server reads the request, but never answers.
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0_u8; 256];
+            while stream.read(&mut buf).unwrap_or(0) > 0 {}
+        });
+
+        let mut stream = connect(address, Duration::from_millis(100)).unwrap();
+        let result = json_request(&mut stream, WORLDTIMEAPI_PAYLOAD);
+        assert!(matches!(result, Err(ClockError::Timeout)));
+        drop(stream);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn clock_offset_tolerance_boundary() {
+        let one_off = ClockOffset { worldtimeapi: Some(1), ntp: Some(-1) };
+        assert!(one_off.is_synced());
+        assert!(one_off.is_within(DEFAULT_CLOCK_TOLERANCE_SECS));
+        assert!(!one_off.is_within(0));
+
+        let five_off = ClockOffset { worldtimeapi: Some(0), ntp: Some(-5) };
+        assert!(!five_off.is_synced());
+        assert!(five_off.is_within(5));
+
+        // Sources that weren't polled don't count.
+        let api_only = ClockOffset { worldtimeapi: Some(1), ntp: None };
+        assert!(api_only.is_synced());
+    }
+
+    #[cfg(feature = "clock")]
+    #[test]
+    fn clock_check_gives_up_after_retries() {
+        let skewed = ClockOffset { worldtimeapi: Some(10), ntp: None };
+        let mut attempts = 0;
+        let result = retry_clock_check(
+            || {
+                attempts += 1;
+                Ok(skewed)
+            },
+            DEFAULT_CLOCK_TOLERANCE_SECS,
+            RETRIES,
+            Duration::from_millis(1),
+        );
+        assert!(matches!(result, Err(ClockError::InvalidClock)));
+        assert_eq!(attempts, RETRIES);
+
+        // Check succeeds as soon as the offset is within tolerance.
+        let synced = ClockOffset { worldtimeapi: Some(1), ntp: None };
+        let mut offsets = vec![synced, skewed];
+        let result = retry_clock_check(|| Ok(offsets.pop().unwrap()), 2, 3, Duration::ZERO);
+        assert_eq!(result.unwrap(), synced);
+        assert!(offsets.is_empty());
+
+        // Failing sources are retried like skewed ones, reporting their last error.
+        let mut attempts = 0;
+        let result = retry_clock_check(
+            || {
+                attempts += 1;
+                Err(ClockError::Ntp(format!("timeout {}", attempts)))
+            },
+            DEFAULT_CLOCK_TOLERANCE_SECS,
+            RETRIES,
+            Duration::from_millis(1),
+        );
+        assert!(matches!(result, Err(ClockError::Ntp(e)) if e == format!("timeout {}", RETRIES)));
+        assert_eq!(attempts, RETRIES);
+
+        // Transient failures recover on a later attempt.
+        let mut results = vec![Ok(synced), Err(ClockError::Ntp(String::from("timeout")))];
+        let result = retry_clock_check(|| results.pop().unwrap(), 2, 3, Duration::ZERO);
+        assert_eq!(result.unwrap(), synced);
+    }
+
+    #[test]
+    fn clock_check_modes() {
+        assert!(ClockCheckMode::Both.polls(TimeSource::WorldTimeApi));
+        assert!(ClockCheckMode::Both.polls(TimeSource::Ntp));
+        assert!(!ClockCheckMode::NtpOnly.polls(TimeSource::WorldTimeApi));
+        assert!(ClockCheckMode::NtpOnly.polls(TimeSource::Ntp));
+        assert!(ClockCheckMode::ApiOnly.polls(TimeSource::WorldTimeApi));
+        assert!(!ClockCheckMode::ApiOnly.polls(TimeSource::Ntp));
+
+        let error = ClockError::Ntp(String::from("timeout")).at_source(TimeSource::Ntp);
+        assert_eq!(error.to_string(), "ntp time source failed: NTP error: timeout");
+    }
+
+    #[test]
+    fn blocking_task_runs_on_another_thread() {
+        let caller = thread::current().id();
+        let task = BlockingTask::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            thread::current().id()
+        });
+        assert_ne!(block_on(task), caller);
+
+        let task = BlockingTask::spawn(|| -> u8 { panic!("blocking task failed") });
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| block_on(task)));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_clock_moves_only_when_told() {
+        let genesis_time = SystemTime::UNIX_EPOCH;
+        let clock = TestClock::new(genesis_time, 5);
+        let shared = clock.clone();
+        assert_eq!(clock.now(), genesis_time);
+
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(shared.now(), genesis_time + Duration::from_secs(3));
+        // Middle of epoch 2, with epochs lasting 10 seconds.
+        shared.set_epoch(2);
+        assert_eq!(clock.now(), genesis_time + Duration::from_secs(25));
+    }
 }
@@ -0,0 +1,18 @@
+use std::fmt::Debug;
+
+/// This trait represents the validity rules the protocol defines for transactions.
+/// Nodes only accept transactions passing them into their mempool.
+pub trait TransactionValidator: Debug + Send + Sync {
+    /// Checks if provided transaction is valid.
+    fn is_valid(&self, transaction: &str) -> bool;
+}
+
+/// Every transaction is considered valid. Default validator.
+#[derive(Debug, Clone, Copy)]
+pub struct AcceptAllTransactions;
+
+impl TransactionValidator for AcceptAllTransactions {
+    fn is_valid(&self, _transaction: &str) -> bool {
+        true
+    }
+}
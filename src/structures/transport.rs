@@ -0,0 +1,216 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+use super::{error::TransportError, message::NetworkMessage, node::Node, vote::Vote};
+
+/// This trait represents the means nodes use to deliver messages to each other,
+/// e.g. in-process calls or a network connection.
+pub trait Transport {
+    /// Delivers provided transaction to the node with provided id.
+    fn send_transaction(&self, to: u64, transaction: &str) -> Result<(), TransportError>;
+
+    /// Delivers provided block proposal(mapped as Vote) to the node with provided id.
+    fn send_proposal(&self, to: u64, proposal: &Vote) -> Result<(), TransportError>;
+
+    /// Delivers provided vote to the node with provided id.
+    fn send_vote(&self, to: u64, vote: &Vote) -> Result<(), TransportError>;
+}
+
+/// In-process transport, delivering messages by directly calling the recipient nodes.
+/// Since the sender is borrowed separately, it can't be a recipient itself.
+#[derive(Debug)]
+pub struct LocalTransport<'a> {
+    nodes: RefCell<Vec<&'a mut Node>>,
+}
+
+impl<'a> LocalTransport<'a> {
+    pub fn new(nodes: Vec<&'a mut Node>) -> LocalTransport<'a> {
+        LocalTransport { nodes: RefCell::new(nodes) }
+    }
+
+    /// Hands provided message to the node with provided id. Node errors are reported as
+    /// rejections of the message.
+    fn deliver(&self, to: u64, message: NetworkMessage) -> Result<(), TransportError> {
+        let mut nodes = self.nodes.borrow_mut();
+        let node =
+            nodes.iter_mut().find(|node| node.id == to).ok_or(TransportError::UnknownPeer(to))?;
+        node.receive_message(message)
+            .map_err(|error| TransportError::Rejected { to, error: Box::new(error) })
+    }
+}
+
+impl Transport for LocalTransport<'_> {
+    fn send_transaction(&self, to: u64, transaction: &str) -> Result<(), TransportError> {
+        self.deliver(to, NetworkMessage::Transaction(transaction.to_string()))
+    }
+
+    fn send_proposal(&self, to: u64, proposal: &Vote) -> Result<(), TransportError> {
+        self.deliver(to, NetworkMessage::Proposal(proposal.clone()))
+    }
+
+    fn send_vote(&self, to: u64, vote: &Vote) -> Result<(), TransportError> {
+        self.deliver(to, NetworkMessage::Vote(vote.clone()))
+    }
+}
+
+/// In-memory transport, queueing messages in a channel per node. Each node drains the
+/// receiving end of its channel, handling messages using Node::receive_message.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelTransport {
+    senders: HashMap<u64, Sender<NetworkMessage>>,
+}
+
+impl ChannelTransport {
+    pub fn new() -> ChannelTransport {
+        ChannelTransport::default()
+    }
+
+    /// Opens a channel towards the node with provided id, returning its receiving end.
+    /// A previously opened channel for the same id is replaced.
+    pub fn connect(&mut self, id: u64) -> Receiver<NetworkMessage> {
+        let (sender, receiver) = channel();
+        self.senders.insert(id, sender);
+        receiver
+    }
+
+    /// Queues provided message in the channel of the node with provided id.
+    fn send(&self, to: u64, message: NetworkMessage) -> Result<(), TransportError> {
+        let sender = self.senders.get(&to).ok_or(TransportError::UnknownPeer(to))?;
+        sender.send(message).map_err(|_| TransportError::Disconnected(to))
+    }
+}
+
+impl Transport for ChannelTransport {
+    fn send_transaction(&self, to: u64, transaction: &str) -> Result<(), TransportError> {
+        self.send(to, NetworkMessage::Transaction(transaction.to_string()))
+    }
+
+    fn send_proposal(&self, to: u64, proposal: &Vote) -> Result<(), TransportError> {
+        self.send(to, NetworkMessage::Proposal(proposal.clone()))
+    }
+
+    fn send_vote(&self, to: u64, vote: &Vote) -> Result<(), TransportError> {
+        self.send(to, NetworkMessage::Vote(vote.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, time::SystemTime};
+
+    use super::{ChannelTransport, LocalTransport, Transport};
+    use crate::structures::{
+        error::{NodeError, TransportError},
+        test_utils::test_nodes,
+        time::TestClock,
+        vote::Vote,
+    };
+
+    /// Transport recording deliveries, failing towards provided unreachable node.
+    #[derive(Default)]
+    struct RecordingTransport {
+        deliveries: RefCell<Vec<(u64, String)>>,
+        unreachable: Option<u64>,
+    }
+
+    impl Transport for RecordingTransport {
+        fn send_transaction(&self, to: u64, transaction: &str) -> Result<(), TransportError> {
+            if self.unreachable == Some(to) {
+                return Err(TransportError::UnknownPeer(to))
+            }
+            self.deliveries.borrow_mut().push((to, transaction.to_string()));
+            Ok(())
+        }
+
+        fn send_proposal(&self, _to: u64, _proposal: &Vote) -> Result<(), TransportError> {
+            Ok(())
+        }
+
+        fn send_vote(&self, _to: u64, _vote: &Vote) -> Result<(), TransportError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn broadcast_reaches_every_peer() {
+        let nodes = test_nodes(3, SystemTime::now());
+        let transport = RecordingTransport::default();
+        nodes[1].broadcast_via(&transport, "tx0").unwrap();
+        let expected = vec![(0, String::from("tx0")), (2, String::from("tx0"))];
+        assert_eq!(*transport.deliveries.borrow(), expected);
+
+        // Failed deliveries are reported, after trying every peer.
+        let transport = RecordingTransport { unreachable: Some(0), ..Default::default() };
+        let result = nodes[1].broadcast_via(&transport, "tx1");
+        assert!(matches!(result, Err(NodeError::Transport(TransportError::UnknownPeer(0)))));
+        assert_eq!(*transport.deliveries.borrow(), vec![(2, String::from("tx1"))]);
+    }
+
+    #[test]
+    fn local_transport_delivers_to_nodes() {
+        let mut nodes = test_nodes(3, SystemTime::now());
+        let (sender, rest) = nodes.split_first_mut().unwrap();
+        rest[1].max_mempool = 0;
+        let transport = LocalTransport::new(rest.iter_mut().collect());
+        let result = sender.broadcast_via(&transport, "tx0");
+        assert!(matches!(
+            result,
+            Err(NodeError::Transport(TransportError::Rejected { to: 2, .. }))
+        ));
+        assert!(matches!(
+            transport.send_transaction(0, "tx0"),
+            Err(TransportError::UnknownPeer(0))
+        ));
+        drop(transport);
+        assert_eq!(nodes[1].pending_transactions(), [String::from("tx0")]);
+        assert!(nodes[2].pending_transactions().is_empty());
+    }
+
+    #[test]
+    fn channel_transport_runs_an_epoch() {
+        let mut nodes = test_nodes(3, SystemTime::now());
+        let clock = TestClock::new(nodes[0].genesis_time, nodes[0].delta());
+        clock.set_epoch(1);
+        let mut transport = ChannelTransport::new();
+        let mut receivers = Vec::new();
+        for node in &mut nodes {
+            node.clock = Box::new(clock.clone());
+            node.receive_transaction(String::from("tx0")).unwrap();
+            receivers.push(transport.connect(node.id));
+        }
+
+        let leader = nodes.iter().position(|node| node.check_if_epoch_leader()).unwrap();
+        nodes[leader].propose_block().unwrap().unwrap();
+        nodes[leader].flush_outgoing(&transport).unwrap();
+        // Proposal reaches every node, producing votes, which reach every node in turn.
+        for _ in 0..2 {
+            for (node, receiver) in nodes.iter_mut().zip(&receivers) {
+                for message in receiver.try_iter() {
+                    node.receive_message(message).unwrap();
+                }
+                node.flush_outgoing(&transport).unwrap();
+            }
+        }
+
+        for node in &nodes {
+            let block = &node.node_blockchains[0].blocks[0];
+            assert_eq!(block.txs(), vec![String::from("tx0")]);
+            assert_eq!(block.vote_count(), 3);
+            assert!(block.metadata.is_notarized());
+        }
+
+        // Dropped receivers report their node as disconnected.
+        drop(receivers);
+        assert!(matches!(
+            transport.send_transaction(0, "tx1"),
+            Err(TransportError::Disconnected(0))
+        ));
+        assert!(matches!(
+            transport.send_transaction(3, "tx1"),
+            Err(TransportError::UnknownPeer(3))
+        ));
+    }
+}
@@ -1,7 +1,15 @@
-use super::block::Block;
+use openssl::pkey::{PKey, Public};
+
+use super::{block::Block, crypto::scheme_for_key};
+
+/// Domain separation tag of block proposal signatures.
+pub const PROPOSAL_DOMAIN: &[u8] = b"streamlet-proposal";
+
+/// Domain separation tag of vote signatures.
+pub const VOTE_DOMAIN: &[u8] = b"streamlet-vote";
 
 /// This struct represents a tuple of the form (vote, B, id).
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Vote {
     /// signed block
     pub vote: Vec<u8>,
@@ -15,4 +23,91 @@ impl Vote {
     pub fn new(vote: Vec<u8>, block: Block, id: u64) -> Vote {
         Vote { vote, block, id }
     }
+
+    /// Bytes a node signs to propose or vote on provided block: the domain tag, the signer id
+    /// and the block signature encoding. Binding the id and the domain makes signatures
+    /// non-transferable between nodes, or between proposals and votes.
+    pub fn signing_payload(domain: &[u8], block: &Block, id: u64) -> Vec<u8> {
+        let mut payload = domain.to_vec();
+        payload.push(0);
+        payload.extend_from_slice(&id.to_be_bytes());
+        payload.extend_from_slice(&block.signature_encode());
+        payload
+    }
+
+    /// Checks if both votes are cast by the same node for the same block, regardless of their
+    /// signature bytes. Vote equality compares signatures too.
+    pub fn same_intent(&self, other: &Vote) -> bool {
+        self.id == other.id && self.block == other.block
+    }
+
+    /// Verifies the vote signature against provided public key, without any node state.
+    /// Signature scheme is derived from the key type. Malformed signatures don't verify.
+    pub fn verify(&self, public_key: &PKey<Public>) -> bool {
+        let payload = Vote::signing_payload(VOTE_DOMAIN, &self.block, self.id);
+        scheme_for_key(public_key).verify(public_key, &payload, &self.vote).unwrap_or(false)
+    }
+}
+
+impl_serde_struct!(Vote { vote: Vec<u8>, block: Block, id: u64 });
+
+/// This struct represents the consensus events a received vote caused.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VoteOutcome {
+    /// Vote notarized its block
+    pub notarized: bool,
+    /// Blocks finalized due to the notarization, in chain order
+    pub finalized: Vec<Block>,
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::pkey::PKey;
+
+    use super::{Vote, PROPOSAL_DOMAIN, VOTE_DOMAIN};
+    use crate::structures::{
+        block::Block,
+        crypto::{public_key, Ed25519Scheme, SignatureScheme},
+        metadata::Metadata,
+    };
+
+    #[test]
+    fn standalone_verification() {
+        let keypair = PKey::generate_ed25519().unwrap();
+        let key = public_key(&keypair).unwrap();
+        let block = Block::new(Block::genesis().hash_digest(), 1, vec![String::from("tx0")]);
+        let sign = |domain| {
+            let payload = Vote::signing_payload(domain, &block, 2);
+            Ed25519Scheme.sign(&keypair, &payload).unwrap()
+        };
+        let vote = Vote::new(sign(VOTE_DOMAIN), block.clone(), 2);
+        assert!(vote.verify(&key));
+
+        // Tampered block, id or signature, and proposal signatures, don't verify.
+        let mut tampered = vote.clone();
+        tampered.block.txs_mut().push(String::from("tx1"));
+        assert!(!tampered.verify(&key));
+        assert!(!Vote::new(vote.vote.clone(), block.clone(), 3).verify(&key));
+        assert!(!Vote::new(vec![0; 3], block.clone(), 2).verify(&key));
+        assert!(!Vote::new(sign(PROPOSAL_DOMAIN), block, 2).verify(&key));
+        let other_key = public_key(&PKey::generate_ed25519().unwrap()).unwrap();
+        assert!(!vote.verify(&other_key));
+    }
+
+    #[test]
+    fn same_intent_ignores_signatures() {
+        let block = Block::new(Block::genesis().hash_digest(), 1, vec![]);
+        let vote = Vote::new(vec![1, 2, 3], block.clone(), 0);
+        let resigned = Vote::new(vec![4, 5, 6], block.clone(), 0);
+        assert_ne!(vote, resigned);
+        assert!(vote.same_intent(&resigned));
+        assert!(!vote.same_intent(&Vote::new(vec![1, 2, 3], block.clone(), 1)));
+        let other_block = Block::new(Block::genesis().hash_digest(), 2, vec![]);
+        assert!(!vote.same_intent(&Vote::new(vec![1, 2, 3], other_block, 0)));
+
+        let mut metadata = Metadata::new();
+        assert!(metadata.add_vote(vote));
+        assert!(!metadata.add_vote(resigned));
+        assert_eq!(metadata.vote_count(), 1);
+    }
 }